@@ -22,6 +22,10 @@ impl Api {
                 "app_revision": revision,
             }),
         )?;
+        // Nothing was updated, so there is no channel to verify.
+        if self.authentication.config.dry_run {
+            return Ok(());
+        }
 
         #[derive(Clone, Debug, Default, PartialEq, Deserialize)]
         struct Channel {
@@ -26,17 +26,22 @@ impl Api {
         Ok(true)
     }
 
-    pub fn create_version(&self, json: HashMap<&str, Value>) -> Result<u32, CommandError> {
+    /// Creates a version and returns its revision, or `None` in a dry run.
+    pub fn create_version(&self, json: HashMap<&str, Value>) -> Result<Option<u32>, CommandError> {
         let response = commands::post(
             &self.authentication,
             "v4/edge-apps/versions?select=revision",
             &json,
         )?;
+        // Nothing was created, so there is no revision to read back.
+        if self.authentication.config.dry_run {
+            return Ok(None);
+        }
         if let Some(arr) = response.as_array() {
             if let Some(obj) = arr.first() {
                 if let Some(revision) = obj["revision"].as_u64() {
                     debug!("New version revision: {revision}");
-                    return Ok(revision as u32);
+                    return Ok(Some(revision as u32));
                 }
             }
         }
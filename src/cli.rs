@@ -1,16 +1,24 @@
 use std::io::{Read, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
 use std::{env, fs, io};
 
-use clap::{Parser, Subcommand};
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
 use http_auth_basic::Credentials;
-use log::{error, info};
+use log::{debug, error, info, warn};
 use reqwest::StatusCode;
 use rpassword::read_password;
 use thiserror::Error;
 
-use crate::authentication::{verify_and_store_token, Authentication, AuthenticationError, Config};
+use crate::authentication::{
+    verify_and_store_token, Authentication, AuthenticationError, Config, CredentialBackend,
+    Environment, Overrides,
+};
 use crate::commands;
+use crate::commands::asset_group::AssetGroupCommand;
+use crate::commands::completions::{self, IdKind};
 use crate::commands::edge_app::instance_manifest::InstanceManifest;
 use crate::commands::edge_app::manifest::EdgeAppManifest;
 use crate::commands::edge_app::server::MOCK_DATA_FILENAME;
@@ -18,16 +26,33 @@ use crate::commands::edge_app::utils::{
     transform_edge_app_path_to_manifest, transform_instance_path_to_instance_manifest,
     validate_manifests_dependacies,
 };
+use crate::commands::inventory;
+use crate::commands::label::LabelCommand;
+use crate::commands::pagination::{Pages, Pagination, DEFAULT_PAGE_SIZE};
 use crate::commands::playlist::PlaylistCommand;
-use crate::commands::{CommandError, Formatter, OutputType, PlaylistFile};
+use crate::commands::predicate_builder::{Schedule, TimeWindow, Weekdays};
+use crate::commands::profile::ProfileCommand;
+use crate::commands::schedule::{self, Timezone};
+use crate::commands::screen::{Coordinates, ScreenUpdate};
+use crate::commands::whoami::{WhoamiCommand, TOKEN_EXPIRED, TOKEN_EXPIRING, TOKEN_REJECTED};
+use crate::commands::{
+    Assets, CommandError, FormatOptions, Formatter, FormatterValue, ImportReport, Labels,
+    OutputTemplate, OutputType, PlaylistFile, Playlists, RowFilter, Screens, TemplateScope,
+};
 const DEFAULT_ASSET_DURATION: u32 = 15;
 
+/// The global `--output` and `--template` options, set once by `handle_cli`.
+static OUTPUT_OPTIONS: OnceLock<FormatOptions> = OnceLock::new();
+
 /// Returns a user-friendly error message for authentication errors.
 fn get_authentication_error_message(e: &AuthenticationError) -> String {
     match e {
         AuthenticationError::Io(io_err) if io_err.kind() == std::io::ErrorKind::NotFound => {
             "Not logged in. Please run `screenly login` first to authenticate.".to_string()
         }
+        AuthenticationError::NoCredentials => {
+            "Not logged in. Please run `screenly login` first to authenticate.".to_string()
+        }
         _ => {
             format!("Authentication error: {e}. Please run `screenly login` to authenticate.")
         }
@@ -37,7 +62,10 @@ fn get_authentication_error_message(e: &AuthenticationError) -> String {
 /// Creates an Authentication instance or exits with a user-friendly error message.
 fn get_authentication() -> Authentication {
     match Authentication::new() {
-        Ok(auth) => auth,
+        Ok(auth) => {
+            log_environment(&auth.config);
+            auth
+        }
         Err(e) => {
             error!("{}", get_authentication_error_message(&e));
             std::process::exit(1);
@@ -45,6 +73,14 @@ fn get_authentication() -> Authentication {
     }
 }
 
+fn log_environment(config: &Config) {
+    match config.environment() {
+        Environment::Custom(url) => debug!("Using the API at {url}"),
+        environment => debug!("Using the {environment} environment ({})", config.url),
+    }
+    debug!("Using profile \"{}\"", config.profile);
+}
+
 #[derive(Error, Debug)]
 enum ParseError {
     #[error("missing \"=\" symbol")]
@@ -64,20 +100,166 @@ fn parse_key_val(s: &str) -> Result<(String, String), ParseError> {
 )]
 #[command(propagate_version = true)]
 pub struct Cli {
-    /// Enables JSON output.
-    #[arg(short, long, action = clap::ArgAction::SetTrue)]
-    json: Option<bool>,
+    #[command(flatten)]
+    pub global: GlobalArgs,
 
     #[command(subcommand)]
     pub(crate) command: Commands,
 }
 
+/// Row selection and presentation options of list commands.
+#[derive(Args, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ListArgs {
+    /// Comma-separated fields to show instead of the default columns, e.g. `name,last_ping`.
+    #[arg(long, value_delimiter = ',')]
+    pub columns: Option<Vec<String>>,
+
+    /// Field to sort rows by. Prefix it with `-` to sort in descending order.
+    #[arg(long, allow_hyphen_values = true)]
+    pub sort_by: Option<String>,
+
+    /// Keeps rows whose field equals a value (`key=value`, case-insensitive) or matches a regular expression (`key~regex`). Can be repeated.
+    #[arg(long)]
+    pub filter: Vec<RowFilter>,
+}
+
+impl ListArgs {
+    pub fn format_options(&self, output_type: OutputType) -> FormatOptions {
+        FormatOptions {
+            columns: self.columns.clone(),
+            sort_by: self.sort_by.clone(),
+            filters: self.filter.clone(),
+            ..FormatOptions::new(output_type)
+        }
+    }
+}
+
+/// Pagination options of list commands.
+#[derive(Args, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PaginationArgs {
+    /// Maximum number of rows to return. All rows are returned by default.
+    #[arg(long)]
+    pub limit: Option<usize>,
+
+    /// Number of rows fetched per request.
+    #[arg(
+        long,
+        default_value_t = DEFAULT_PAGE_SIZE,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub page_size: usize,
+}
+
+impl PaginationArgs {
+    pub fn pagination(&self) -> Pagination {
+        Pagination {
+            limit: self.limit,
+            page_size: self.page_size,
+        }
+    }
+}
+
+/// Schedule options that build a playlist predicate.
+#[derive(Args, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[group(id = "schedule", multiple = true)]
+pub struct ScheduleArgs {
+    /// Days the playlist is shown on, e.g. `mon-fri` or `sat,sun`.
+    #[arg(long)]
+    pub weekdays: Option<Weekdays>,
+
+    /// Time of day the playlist is shown, e.g. `09:00-17:00`. The end is excluded, and a window such as `22:00-06:00` runs past midnight.
+    #[arg(long)]
+    pub between: Option<TimeWindow>,
+
+    /// First day the playlist is shown, e.g. `2026-12-01`.
+    #[arg(long)]
+    pub from_date: Option<chrono::NaiveDate>,
+
+    /// Last day the playlist is shown, included.
+    #[arg(long)]
+    pub until_date: Option<chrono::NaiveDate>,
+
+    /// Shows the playlist at all times.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with_all = ["weekdays", "between", "from_date", "until_date"])]
+    pub always: bool,
+}
+
+impl ScheduleArgs {
+    pub fn schedule(&self) -> Schedule {
+        Schedule {
+            weekdays: self.weekdays.clone(),
+            between: self.between,
+            from_date: self.from_date,
+            until_date: self.until_date,
+            always: self.always,
+        }
+    }
+}
+
+// Options shared by all commands. Not a doc comment: it would replace the about text of `Cli`.
+#[derive(Args, Clone, Debug, Default)]
+pub struct GlobalArgs {
+    /// Authentication profile to use. Defaults to the SCREENLY_PROFILE environment variable or the active profile.
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
+    /// API environment to use: prod, stage, local or an http(s):// URL. Takes precedence over API_BASE_URL. Defaults to the SCREENLY_ENVIRONMENT environment variable or the profile's environment. `login` saves it in the profile.
+    #[arg(long, global = true)]
+    pub environment: Option<Environment>,
+
+    /// Output format of commands that print API resources. `--json` on a subcommand is a shorthand for `--output json`. [default: table]
+    #[arg(short, long, global = true, value_enum)]
+    pub output: Option<OutputType>,
+
+    /// Renders the output through a Jinja-style template instead, e.g. `{{ name }}: {{ status }}`. Use `@path` to read the template from a file.
+    #[arg(long, global = true)]
+    pub template: Option<String>,
+
+    /// Whether the template is rendered for every item or once for the whole collection.
+    #[arg(long, global = true, value_enum, default_value_t)]
+    pub template_scope: TemplateScope,
+
+    /// How many times rate-limited or temporarily failing requests are retried. Defaults to the SCREENLY_MAX_RETRIES environment variable or 3.
+    #[arg(long, global = true)]
+    pub max_retries: Option<u32>,
+
+    /// Initial delay between retries in milliseconds, doubled on every attempt. Defaults to the SCREENLY_RETRY_BASE_DELAY_MS environment variable or 500.
+    #[arg(long, global = true)]
+    pub retry_base_delay: Option<u64>,
+
+    /// Also retries POST and PATCH requests on gateway errors and connection failures. Can be enabled with SCREENLY_RETRY_NON_IDEMPOTENT=true.
+    #[arg(long, global = true)]
+    pub retry_non_idempotent: bool,
+
+    /// Prints the requests that would change anything (method, URL and payload) instead of sending them.
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     /// Logs in with the provided token and stores it for further use if valid. You can set the API_TOKEN environment variable to override the stored token.
     Login {},
     /// Logs out and removes the stored token.
     Logout {},
+    /// Shows the account, team, token scope and API URL of the active credentials.
+    Whoami {
+        /// Enables JSON output.
+        #[arg(short, long, action = clap::ArgAction::SetTrue)]
+        json: Option<bool>,
+    },
+    /// Checks the active token and warns when it is about to expire or was rejected.
+    Status {
+        /// Enables JSON output.
+        #[arg(short, long, action = clap::ArgAction::SetTrue)]
+        json: Option<bool>,
+        /// Warn when the token expires within this many days.
+        #[arg(long, default_value_t = 7)]
+        warn_days: i64,
+    },
+    /// Authentication profile commands.
+    #[command(subcommand)]
+    Profile(ProfileCommands),
     /// Screen related commands.
     #[command(subcommand)]
     Screen(ScreenCommands),
@@ -87,14 +269,64 @@ pub enum Commands {
     /// Playlist related commands.
     #[command(subcommand)]
     Playlist(PlaylistCommands),
+    /// Asset group related commands. Asset groups are folders for assets.
+    #[command(subcommand)]
+    AssetGroup(AssetGroupCommands),
+    /// Label related commands.
+    #[command(subcommand)]
+    Label(LabelCommands),
     /// Edge App related commands.
     #[command(subcommand)]
     EdgeApp(EdgeAppCommands),
     /// Starts the MCP (Model Context Protocol) server on stdio for AI assistant integration.
     Mcp {},
+    /// Prints the shell completion script, e.g. `source <(screenly completions bash)`. Bash, zsh and fish also complete screen, asset and playlist ids seen by the list commands.
+    Completions {
+        /// Shell to generate the script for.
+        shell: Shell,
+    },
+    /// Prints the man page, or writes a page per command into a directory.
+    Man {
+        /// Directory to write `screenly.1` and the subcommand pages into.
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
     /// For generating `docs/CommandLineHelp.md`.
     #[clap(hide = true)]
     PrintHelpMarkdown {},
+    /// Prints cached ids for the shell completion scripts.
+    #[clap(hide = true)]
+    CompleteIds { kind: IdKind },
+}
+
+#[derive(Subcommand, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProfileCommands {
+    /// Lists your authentication profiles.
+    List {
+        /// Enables JSON output.
+        #[arg(short, long, action = clap::ArgAction::SetTrue)]
+        json: Option<bool>,
+
+        #[command(flatten)]
+        list: ListArgs,
+    },
+    /// Makes a profile the active one for further commands.
+    Use {
+        /// Name of the profile.
+        name: String,
+    },
+    /// Removes a profile together with its stored token.
+    Remove {
+        /// Name of the profile.
+        name: String,
+    },
+    /// Moves plaintext tokens (e.g. `~/.screenly`) into a secure credential store.
+    Migrate {
+        /// Credential store to use: keyring, encrypted-file or file. `file` keeps plaintext tokens where they are. Defaults to the keyring when it is available.
+        /// The SCREENLY_CREDENTIAL_BACKEND environment variable overrides the stored choice.
+        #[arg(short, long)]
+        backend: Option<CredentialBackend>,
+    },
 }
 
 #[derive(Subcommand, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -104,13 +336,23 @@ pub enum ScreenCommands {
         /// Enables JSON output.
         #[arg(short, long, action = clap::ArgAction::SetTrue)]
         json: Option<bool>,
+
+        /// Only lists screens with this label (id, name or `name:<glob>` selector).
+        #[arg(long)]
+        label: Option<String>,
+
+        #[command(flatten)]
+        pagination: PaginationArgs,
+
+        #[command(flatten)]
+        list: ListArgs,
     },
     /// Gets a single screen by id.
     Get {
         /// Enables JSON output.
         #[arg(short, long, action = clap::ArgAction::SetTrue)]
         json: Option<bool>,
-        /// UUID of the screen.
+        /// UUID, name or `name:<glob>` selector of the screen.
         uuid: String,
     },
     /// Adds a new screen.
@@ -123,13 +365,190 @@ pub enum ScreenCommands {
         /// Optional name of the new screen.
         name: Option<String>,
     },
+    /// Registers the screens of a CSV or YAML inventory.
+    ///
+    /// Each row takes a `pin`, and optionally a `name`, `labels` (separated by `;` in CSV),
+    /// `location` and `playlist`. Missing labels are created. The playlist is shown on the
+    /// screen of its row only, like `playlist assign --screen`. Prints a report with the
+    /// outcome of every row and exits with a non-zero code when any row failed.
+    Import {
+        /// Enables JSON output.
+        #[arg(short, long, action = clap::ArgAction::SetTrue)]
+        json: Option<bool>,
+        /// Path to a `.csv`, `.yml` or `.yaml` inventory file.
+        path: PathBuf,
+        /// Number of screens registered in parallel.
+        #[arg(long, default_value_t = 4)]
+        jobs: usize,
+    },
+    /// Lists the playlists that reach a screen through its labels and which of them should be playing now.
+    Playlists {
+        /// Enables JSON output.
+        #[arg(short, long, action = clap::ArgAction::SetTrue)]
+        json: Option<bool>,
+        /// UUID, name or `name:<glob>` selector of the screen.
+        uuid: String,
+        /// Only lists the playlists that should be playing now.
+        #[arg(long)]
+        playing: bool,
+
+        #[command(flatten)]
+        list: ListArgs,
+    },
+    /// Summarises the online state, last ping, software version, uptime and playlist of screens.
+    Status {
+        /// Enables JSON output.
+        #[arg(short, long, action = clap::ArgAction::SetTrue)]
+        json: Option<bool>,
+        /// Only includes screens that carry this label (id, name or `name:<glob>` selector).
+        #[arg(long)]
+        label: Option<String>,
+        /// Refreshes the summary every SECONDS seconds until interrupted. Output formats other than
+        /// the table print one snapshot after another.
+        #[arg(long, value_name = "SECONDS", num_args = 0..=1, default_missing_value = "30", value_parser = clap::value_parser!(u64).range(1..))]
+        watch: Option<u64>,
+        /// Exits with code 2 when any selected screen is offline, e.g. for Nagios-style checks.
+        #[arg(long, conflicts_with = "watch")]
+        exit_code: bool,
+
+        #[command(flatten)]
+        list: ListArgs,
+    },
+    /// Updates the name, location, coordinates or notes of a screen.
+    Update {
+        /// Enables JSON output.
+        #[arg(short, long, action = clap::ArgAction::SetTrue)]
+        json: Option<bool>,
+        /// UUID, name or `name:<glob>` selector of the screen.
+        uuid: String,
+        /// New name of the screen.
+        #[arg(long)]
+        name: Option<String>,
+        /// Where the screen is, e.g. "Lobby, 2nd floor". An empty value clears it.
+        #[arg(long)]
+        location: Option<String>,
+        /// Latitude and longitude in degrees, e.g. `59.33,18.07`.
+        #[arg(long, allow_hyphen_values = true)]
+        coordinates: Option<Coordinates>,
+        /// Notes about the screen. An empty value clears them.
+        #[arg(long)]
+        notes: Option<String>,
+    },
     /// Deletes a screen. This cannot be undone.
     Delete {
-        /// UUID of the screen to be deleted.
+        /// UUID, name or `name:<glob>` selector of the screen to be deleted.
+        uuid: String,
+    },
+}
+
+#[derive(Subcommand, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AssetGroupCommands {
+    /// Lists your asset groups.
+    List {
+        /// Enables JSON output.
+        #[arg(short, long, action = clap::ArgAction::SetTrue)]
+        json: Option<bool>,
+
+        #[command(flatten)]
+        pagination: PaginationArgs,
+
+        #[command(flatten)]
+        list: ListArgs,
+    },
+    /// Creates a new asset group.
+    Create {
+        /// Enables JSON output.
+        #[arg(short, long, action = clap::ArgAction::SetTrue)]
+        json: Option<bool>,
+        /// Title of the asset group.
+        title: String,
+    },
+    /// Renames an asset group.
+    Update {
+        /// Enables JSON output.
+        #[arg(short, long, action = clap::ArgAction::SetTrue)]
+        json: Option<bool>,
+        /// UUID, title or `name:<glob>` selector of the asset group.
+        uuid: String,
+        /// New title of the asset group.
+        #[arg(long)]
+        title: String,
+    },
+    /// Deletes an asset group together with all assets in it. This cannot be undone.
+    Delete {
+        /// UUID, title or `name:<glob>` selector of the asset group to be deleted.
         uuid: String,
     },
 }
 
+#[derive(Subcommand, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LabelCommands {
+    /// Lists your labels.
+    List {
+        /// Enables JSON output.
+        #[arg(short, long, action = clap::ArgAction::SetTrue)]
+        json: Option<bool>,
+
+        #[command(flatten)]
+        pagination: PaginationArgs,
+
+        #[command(flatten)]
+        list: ListArgs,
+    },
+    /// Creates a new label.
+    Create {
+        /// Enables JSON output.
+        #[arg(short, long, action = clap::ArgAction::SetTrue)]
+        json: Option<bool>,
+        /// Name of the label.
+        name: String,
+    },
+    /// Renames a label.
+    Update {
+        /// Enables JSON output.
+        #[arg(short, long, action = clap::ArgAction::SetTrue)]
+        json: Option<bool>,
+        /// UUID, name or `name:<glob>` selector of the label.
+        uuid: String,
+        /// New name of the label.
+        #[arg(long)]
+        name: String,
+    },
+    /// Deletes a label. Screens and playlists keep working without it.
+    Delete {
+        /// UUID, name or `name:<glob>` selector of the label to be deleted.
+        uuid: String,
+    },
+    /// Adds a label to a screen.
+    LinkScreen {
+        /// UUID, name or `name:<glob>` selector of the label.
+        uuid: String,
+        /// UUID, name or `name:<glob>` selector of the screen.
+        screen: String,
+    },
+    /// Removes a label from a screen.
+    UnlinkScreen {
+        /// UUID, name or `name:<glob>` selector of the label.
+        uuid: String,
+        /// UUID, name or `name:<glob>` selector of the screen.
+        screen: String,
+    },
+    /// Adds a label to a playlist.
+    LinkPlaylist {
+        /// UUID, name or `name:<glob>` selector of the label.
+        uuid: String,
+        /// UUID, title or `name:<glob>` selector of the playlist.
+        playlist: String,
+    },
+    /// Removes a label from a playlist.
+    UnlinkPlaylist {
+        /// UUID, name or `name:<glob>` selector of the label.
+        uuid: String,
+        /// UUID, title or `name:<glob>` selector of the playlist.
+        playlist: String,
+    },
+}
+
 #[derive(Subcommand, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum PlaylistCommands {
     /// Creates a new playlist.
@@ -151,6 +570,9 @@ pub enum PlaylistCommands {
     ///   $WEEKDAY IN {1, 2, 3, 4, 5}             - Weekdays only
     ///   $TIME BETWEEN {32400000, 61200000}     - 9 AM to 5 PM
     ///   NOT $WEEKDAY IN {0, 6}                  - Exclude weekends
+    ///
+    /// Instead of writing a predicate, build one with the schedule options, e.g.
+    /// `--weekdays mon-fri --between 09:00-17:00`.
     Create {
         /// Enables JSON output.
         #[arg(short, long, action = clap::ArgAction::SetTrue)]
@@ -173,24 +595,34 @@ pub enum PlaylistCommands {
             $WEEKDAY IN {1, 2, 3, 4, 5}         - Weekdays only\n  \
             $TIME BETWEEN {32400000, 61200000}  - 9 AM to 5 PM\n  \
             NOT $WEEKDAY IN {0, 6}              - Exclude weekends\n\n\
-            Default: TRUE"
+            Default: TRUE",
+            conflicts_with = "schedule"
         )]
         predicate: Option<String>,
+
+        #[command(flatten)]
+        schedule: ScheduleArgs,
     },
     /// Lists your playlists.
     List {
         /// Enables JSON output.
         #[arg(short, long, action = clap::ArgAction::SetTrue)]
         json: Option<bool>,
+
+        #[command(flatten)]
+        pagination: PaginationArgs,
+
+        #[command(flatten)]
+        list: ListArgs,
     },
     /// Gets a single playlist by id.
     Get {
-        /// UUID of the playlist.
+        /// UUID, title or `name:<glob>` selector of the playlist.
         uuid: String,
     },
     /// Deletes a playlist. This cannot be undone.
     Delete {
-        /// UUID of the playlist to be deleted.
+        /// UUID, title or `name:<glob>` selector of the playlist to be deleted.
         uuid: String,
     },
     /// Adds an asset to the end of the playlist.
@@ -198,9 +630,9 @@ pub enum PlaylistCommands {
         /// Enables JSON output.
         #[arg(short, long, action = clap::ArgAction::SetTrue)]
         json: Option<bool>,
-        /// UUID of the playlist.
+        /// UUID, title or `name:<glob>` selector of the playlist.
         uuid: String,
-        /// UUID of the asset.
+        /// UUID, title or `name:<glob>` selector of the asset.
         asset_uuid: String,
         /// Duration of the playlist item in seconds. Defaults to 15 seconds.
         duration: Option<u32>,
@@ -210,15 +642,124 @@ pub enum PlaylistCommands {
         /// Enables JSON output.
         #[arg(short, long, action = clap::ArgAction::SetTrue)]
         json: Option<bool>,
-        /// UUID of the playlist.
+        /// UUID, title or `name:<glob>` selector of the playlist.
         uuid: String,
-        /// UUID of the asset.
+        /// UUID, title or `name:<glob>` selector of the asset.
         asset_uuid: String,
         /// Duration of the playlist item in seconds. Defaults to 15 seconds.
         duration: Option<u32>,
     },
-    /// Updates a playlist from JSON input on stdin.
-    Update {},
+    /// Updates a playlist from JSON input on stdin, or only its predicate when the playlist is
+    /// given.
+    #[command(group(
+        clap::ArgGroup::new("new_predicate")
+            .multiple(true)
+            .requires("uuid")
+            .args(["predicate", "weekdays", "between", "from_date", "until_date", "always"])
+    ))]
+    Update {
+        /// Enables JSON output.
+        #[arg(short, long, action = clap::ArgAction::SetTrue)]
+        json: Option<bool>,
+        /// UUID, title or `name:<glob>` selector of the playlist whose predicate to replace.
+        #[arg(requires = "new_predicate")]
+        uuid: Option<String>,
+        /// New predicate expression controlling when the playlist is shown.
+        #[arg(long, conflicts_with = "schedule")]
+        predicate: Option<String>,
+
+        #[command(flatten)]
+        schedule: ScheduleArgs,
+    },
+    /// Describes in plain English when a playlist is shown.
+    Explain {
+        /// UUID, title or `name:<glob>` selector of the playlist.
+        uuid: String,
+    },
+    /// Shares a playlist with another team.
+    Share {
+        /// Enables JSON output.
+        #[arg(short, long, action = clap::ArgAction::SetTrue)]
+        json: Option<bool>,
+        /// UUID, title or `name:<glob>` selector of the playlist.
+        uuid: String,
+        /// UUID, name or `name:<glob>` selector of the team.
+        #[arg(long)]
+        team: String,
+    },
+    /// Stops sharing a playlist with a team.
+    Unshare {
+        /// UUID, title or `name:<glob>` selector of the playlist.
+        uuid: String,
+        /// UUID, name or `name:<glob>` selector of the team.
+        #[arg(long)]
+        team: String,
+    },
+    /// Lists the teams playlists are shared with.
+    Shares {
+        /// Enables JSON output.
+        #[arg(short, long, action = clap::ArgAction::SetTrue)]
+        json: Option<bool>,
+        /// UUID, title or `name:<glob>` selector of a playlist. Lists the shares of all playlists when omitted.
+        uuid: Option<String>,
+
+        #[command(flatten)]
+        pagination: PaginationArgs,
+
+        #[command(flatten)]
+        list: ListArgs,
+    },
+    /// Shows a playlist on a screen or on the screens that carry a label.
+    #[command(group(clap::ArgGroup::new("target").required(true).args(["screen", "label"])))]
+    Assign {
+        /// UUID, title or `name:<glob>` selector of the playlist.
+        uuid: String,
+        /// UUID, name or `name:<glob>` selector of the screen. The playlist reaches the screen
+        /// through a label of its own, `screen:<screen id>`, which is created and put on the
+        /// screen if it does not exist yet.
+        #[arg(long)]
+        screen: Option<String>,
+        /// UUID, name or `name:<glob>` selector of the label.
+        #[arg(long)]
+        label: Option<String>,
+    },
+    /// Stops showing a playlist on a screen or on the screens that carry a label.
+    #[command(group(clap::ArgGroup::new("target").required(true).args(["screen", "label"])))]
+    Unassign {
+        /// UUID, title or `name:<glob>` selector of the playlist.
+        uuid: String,
+        /// UUID, name or `name:<glob>` selector of the screen. Unlinks the playlist from the
+        /// screen's `screen:<screen id>` label, which stays on the screen.
+        #[arg(long)]
+        screen: Option<String>,
+        /// UUID, name or `name:<glob>` selector of the label.
+        #[arg(long)]
+        label: Option<String>,
+    },
+    /// Lists when a playlist, or a predicate, would be on air over a range of days.
+    #[command(group(clap::ArgGroup::new("source").required(true).args(["uuid", "predicate"])))]
+    Simulate {
+        /// Enables JSON output.
+        #[arg(short, long, action = clap::ArgAction::SetTrue)]
+        json: Option<bool>,
+        /// UUID, title or `name:<glob>` selector of the playlist.
+        uuid: Option<String>,
+        /// Predicate to simulate instead of a playlist's, e.g. "$WEEKDAY BETWEEN {1, 5}".
+        #[arg(long)]
+        predicate: Option<String>,
+        /// First day of the range, e.g. 2026-10-01. Defaults to today.
+        #[arg(long)]
+        from: Option<chrono::NaiveDate>,
+        /// Last day of the range, included. Defaults to a week after --from.
+        #[arg(long)]
+        to: Option<chrono::NaiveDate>,
+        /// IANA timezone of the screens, e.g. Europe/London.
+        #[arg(long, default_value = "UTC")]
+        tz: Timezone,
+        /// Prints the intervals as an iCalendar feed.
+        #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "json")]
+        ical: bool,
+    },
 }
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
@@ -269,13 +810,19 @@ pub enum AssetCommands {
         /// Enables JSON output.
         #[arg(short, long, action = clap::ArgAction::SetTrue)]
         json: Option<bool>,
+
+        #[command(flatten)]
+        pagination: PaginationArgs,
+
+        #[command(flatten)]
+        list: ListArgs,
     },
     /// Gets a single asset by id.
     Get {
         /// Enables JSON output.
         #[arg(short, long, action = clap::ArgAction::SetTrue)]
         json: Option<bool>,
-        /// UUID of the asset.
+        /// UUID, title or `name:<glob>` selector of the asset.
         uuid: String,
     },
     /// Adds a new asset.
@@ -287,17 +834,38 @@ pub enum AssetCommands {
         path: String,
         /// Asset title.
         title: String,
+        /// UUID, title or `name:<glob>` selector of the asset group to add the asset to.
+        #[arg(long)]
+        group: Option<String>,
+    },
+    /// Moves an asset into an asset group, or out of its group with `--no-group`.
+    Move {
+        /// Enables JSON output.
+        #[arg(short, long, action = clap::ArgAction::SetTrue)]
+        json: Option<bool>,
+        /// UUID, title or `name:<glob>` selector of the asset.
+        uuid: String,
+        /// UUID, title or `name:<glob>` selector of the asset group.
+        #[arg(
+            long,
+            required_unless_present = "no_group",
+            conflicts_with = "no_group"
+        )]
+        group: Option<String>,
+        /// Takes the asset out of its asset group.
+        #[arg(long)]
+        no_group: bool,
     },
 
     /// Deletes an asset. This cannot be undone.
     Delete {
-        /// UUID of the asset to be deleted.
+        /// UUID, title or `name:<glob>` selector of the asset to be deleted.
         uuid: String,
     },
 
     /// Injects JavaScript code inside of the web asset. It will be executed once the asset loads during playback.
     InjectJs {
-        /// UUID of the web asset to inject with JavaScript.
+        /// UUID, title or `name:<glob>` selector of the web asset to inject with JavaScript.
         uuid: String,
 
         /// Path to local file or URL for remote file.
@@ -306,7 +874,7 @@ pub enum AssetCommands {
 
     /// Sets HTTP headers for a web asset.
     SetHeaders {
-        /// UUID of the web asset.
+        /// UUID, title or `name:<glob>` selector of the web asset.
         uuid: String,
 
         /// HTTP headers in the form `header1=value1[,header2=value2[,...]]`. This command
@@ -317,7 +885,7 @@ pub enum AssetCommands {
     },
     /// Updates HTTP headers for a web asset.
     UpdateHeaders {
-        /// UUID of the web asset.
+        /// UUID, title or `name:<glob>` selector of the web asset.
         uuid: String,
 
         /// HTTP headers in the form `header1=value1[,header2=value2[,...]]`. This command updates only the given headers (adding them if new), leaving other headers unchanged.
@@ -327,7 +895,7 @@ pub enum AssetCommands {
 
     /// Sets up basic authentication headers for a web asset.
     BasicAuth {
-        /// UUID of the web asset.
+        /// UUID, title or `name:<glob>` selector of the web asset.
         uuid: String,
         /// Basic authentication credentials in "user=password" form.
         #[arg(value_parser = parse_key_val)]
@@ -335,7 +903,7 @@ pub enum AssetCommands {
     },
     /// Sets up bearer authentication headers for a web asset.
     BearerAuth {
-        /// UUID of the web asset.
+        /// UUID, title or `name:<glob>` selector of the web asset.
         uuid: String,
         /// Bearer token.
         token: String,
@@ -362,6 +930,9 @@ pub enum EdgeAppCommands {
         /// Enables JSON output.
         #[arg(short, long, action = clap::ArgAction::SetTrue)]
         json: Option<bool>,
+
+        #[command(flatten)]
+        list: ListArgs,
     },
     /// Renames an Edge App.
     Rename {
@@ -432,6 +1003,9 @@ pub enum EdgeAppSettingsCommands {
         /// Enables JSON output.
         #[arg(short, long, action = clap::ArgAction::SetTrue)]
         json: Option<bool>,
+
+        #[command(flatten)]
+        list: ListArgs,
     },
     /// Sets an Edge App setting.
     Set {
@@ -456,6 +1030,9 @@ pub enum EdgeAppInstanceCommands {
         /// Enables JSON output.
         #[arg(short, long, action = clap::ArgAction::SetTrue)]
         json: Option<bool>,
+
+        #[command(flatten)]
+        list: ListArgs,
     },
     /// Creates an Edge App instance.
     Create {
@@ -485,22 +1062,36 @@ pub fn handle_command_execution_result<T: Formatter>(
     result: anyhow::Result<T, CommandError>,
     json: &Option<bool>,
 ) {
-    match result {
-        Ok(screen) => {
-            let output_type = if json.unwrap_or(false) {
-                OutputType::Json
-            } else {
-                OutputType::HumanReadable
-            };
-            println!("{}", screen.format(output_type));
+    handle_list_execution_result(result, json, &ListArgs::default());
+}
+
+pub fn handle_list_execution_result<T: Formatter>(
+    result: anyhow::Result<T, CommandError>,
+    json: &Option<bool>,
+    list: &ListArgs,
+) {
+    let output_type = output_type(json);
+    // Formatting fails when the output template cannot be rendered.
+    match result.and_then(|value| value.format_with(&format_options(json, list))) {
+        Ok(output) => {
+            println!("{output}");
         }
         Err(e) => {
+            if matches!(output_type, OutputType::Json | OutputType::Ndjson) {
+                println!("{}", format_error_json(&e));
+            }
             match e {
                 CommandError::Authentication(_) => {
                     error!(
                         "Authentication error occurred. Please use login command to authenticate."
                     )
                 }
+                CommandError::Api(api_error) => {
+                    error!("{api_error}");
+                }
+                CommandError::InvalidPredicate(_) | CommandError::Template(_) => {
+                    error!("{e}");
+                }
                 _ => {
                     error!("Error occurred: {e:?}");
                 }
@@ -510,6 +1101,112 @@ pub fn handle_command_execution_result<T: Formatter>(
     }
 }
 
+/// Prints a listing page by page when the output format allows it, see
+/// [`FormatOptions::streams`], and all at once otherwise. `listed` gets every row once the
+/// listing is complete.
+fn handle_paged_list<T: Formatter>(
+    pages: Pages,
+    new: fn(serde_json::Value) -> T,
+    listed: impl FnOnce(&serde_json::Value),
+    json: &Option<bool>,
+    list: &ListArgs,
+) {
+    let options = format_options(json, list);
+    let limit = pages.limit();
+    let mut rows: Vec<serde_json::Value> = Vec::new();
+    for page in pages {
+        let mut page = match page {
+            Ok(page) => page,
+            Err(e) => return handle_list_execution_result(Err::<T, _>(e), json, list),
+        };
+        if let Some(limit) = limit {
+            page.truncate(limit.saturating_sub(rows.len()));
+        }
+        if options.streams() {
+            match new(serde_json::Value::Array(page.clone())).format_with(&options) {
+                Ok(output) if output.is_empty() => {}
+                Ok(output) => println!("{output}"),
+                Err(e) => return handle_list_execution_result(Err::<T, _>(e), json, list),
+            }
+        }
+        rows.extend(page);
+    }
+
+    let rows = serde_json::Value::Array(rows);
+    listed(&rows);
+    if !options.streams() {
+        handle_list_execution_result(Ok(new(rows)), json, list);
+    }
+}
+
+/// Resolves the output format of a command from its `--json` flag and the global `--output` option.
+fn output_type(json: &Option<bool>) -> OutputType {
+    if json.unwrap_or(false) {
+        OutputType::Json
+    } else {
+        OUTPUT_OPTIONS
+            .get()
+            .map(|options| options.output_type)
+            .unwrap_or_default()
+    }
+}
+
+/// Combines the options of a list command with the global `--output` and `--template` options.
+fn format_options(json: &Option<bool>, list: &ListArgs) -> FormatOptions {
+    FormatOptions {
+        template: OUTPUT_OPTIONS
+            .get()
+            .and_then(|options| options.template.clone()),
+        ..list.format_options(output_type(json))
+    }
+}
+
+/// Renders an error as `{"error": {...}}` so that JSON consumers can tell failures apart.
+fn format_error_json(e: &CommandError) -> String {
+    let error = match e {
+        CommandError::Api(api_error) => serde_json::to_value(api_error).unwrap_or_default(),
+        _ => serde_json::json!({ "message": e.to_string() }),
+    };
+    serde_json::to_string_pretty(&serde_json::json!({ "error": error })).unwrap()
+}
+
+/// The predicate given with `--predicate` or built from the schedule options, if any.
+fn predicate_or_exit(predicate: &Option<String>, schedule: &ScheduleArgs) -> Option<String> {
+    match predicate {
+        Some(predicate) => Some(predicate.clone()),
+        None => schedule.schedule().predicate().unwrap_or_else(|e| {
+            error!("{e}");
+            std::process::exit(1);
+        }),
+    }
+}
+
+// Resolves a resource argument to an id or exits with the lookup error.
+fn resolve_or_exit(result: Result<String, CommandError>) -> String {
+    result.unwrap_or_else(|e| {
+        error!("{e}");
+        std::process::exit(1);
+    })
+}
+
+// Ids are used without looking them up. When a command finds nothing with the id, it runs
+// again on the resource named like the id, if any, since a name may look like an id.
+fn retry_id_as_name<T: FormatterValue>(
+    result: Result<T, CommandError>,
+    resolve_id_as_name: impl FnOnce() -> Result<Option<String>, CommandError>,
+    call: impl FnOnce(&str) -> Result<T, CommandError>,
+) -> Result<T, CommandError> {
+    match result {
+        Ok(found) if found.value().as_array().is_some_and(Vec::is_empty) => {
+            match resolve_id_as_name()? {
+                Some(id) => call(&id),
+                None => Ok(found),
+            }
+        }
+        result => result,
+    }
+}
+
 pub fn get_screen_name(
     id: &str,
     screen_command: &commands::screen::ScreenCommand,
@@ -555,14 +1252,39 @@ pub fn get_asset_title(
 }
 
 pub fn handle_cli(cli: &Cli) {
+    Overrides {
+        profile: cli.global.profile.clone(),
+        environment: cli.global.environment.clone(),
+        max_retries: cli.global.max_retries,
+        retry_base_delay: cli.global.retry_base_delay.map(Duration::from_millis),
+        retry_non_idempotent: cli.global.retry_non_idempotent,
+        dry_run: cli.global.dry_run,
+    }
+    .install();
+    let template = cli.global.template.as_ref().map(|template| {
+        OutputTemplate::new(template, cli.global.template_scope).unwrap_or_else(|e| {
+            error!("{e}");
+            std::process::exit(1);
+        })
+    });
+    OUTPUT_OPTIONS.get_or_init(|| FormatOptions {
+        output_type: cli.global.output.unwrap_or_default(),
+        template,
+        ..Default::default()
+    });
     match &cli.command {
         Commands::Login {} => {
+            let config = Config::default();
+            log_environment(&config);
             print!("Enter your API Token: ");
             std::io::stdout().flush().unwrap();
             let token = read_password().unwrap();
-            match verify_and_store_token(&token, &Config::default().url) {
+            match verify_and_store_token(&token, &config.url, &config.profile) {
                 Ok(()) => {
-                    info!("Login credentials have been saved.");
+                    info!(
+                        "Login credentials have been saved for profile \"{}\".",
+                        config.profile
+                    );
                     std::process::exit(0);
                 }
 
@@ -582,17 +1304,100 @@ pub fn handle_cli(cli: &Cli) {
         Commands::Asset(command) => handle_cli_asset_command(command),
         Commands::EdgeApp(command) => handle_cli_edge_app_command(command),
         Commands::Playlist(command) => handle_cli_playlist_command(command),
+        Commands::AssetGroup(command) => handle_cli_asset_group_command(command),
+        Commands::Label(command) => handle_cli_label_command(command),
+        Commands::Profile(command) => handle_cli_profile_command(command),
         Commands::Logout {} => {
-            Authentication::remove_token().expect("Failed to remove token.");
+            let config = Config::default();
+            Authentication::remove_token(&config.profile).expect("Failed to remove token.");
             info!("Logout successful.");
             std::process::exit(0);
         }
+        Commands::Whoami { json } => {
+            let whoami_command = WhoamiCommand::new(get_authentication());
+            handle_command_execution_result(whoami_command.whoami(chrono::Duration::days(7)), json);
+        }
+        Commands::Status { json, warn_days } => handle_cli_status_command(json, *warn_days),
         Commands::Mcp {} => {
             handle_cli_mcp_command();
         }
+        Commands::Completions { shell } => {
+            let mut cmd = Cli::command();
+            if let Err(e) = completions::generate(*shell, &mut cmd, &mut io::stdout()) {
+                error!("Failed to write the completion script: {e}");
+                std::process::exit(1);
+            }
+        }
+        Commands::Man { dir } => handle_cli_man_command(dir.as_deref()),
         Commands::PrintHelpMarkdown {} => {
             clap_markdown::print_help_markdown::<Cli>();
         }
+        Commands::CompleteIds { kind } => {
+            let profile = Config::default().profile;
+            for line in completions::cached_ids(&profile, *kind) {
+                println!("{line}");
+            }
+        }
+    }
+}
+
+fn handle_cli_man_command(dir: Option<&Path>) {
+    let cmd = Cli::command();
+    let result = match dir {
+        Some(dir) => fs::create_dir_all(dir).and_then(|_| clap_mangen::generate_to(cmd, dir)),
+        None => clap_mangen::Man::new(cmd).render(&mut io::stdout()),
+    };
+    if let Err(e) = result {
+        error!("Failed to write the man page: {e}");
+        std::process::exit(1);
+    }
+}
+
+// Keeps the ids of listed resources around for shell completion. Listings cut short by
+// `--limit` would drop the other ids, `--filter` only applies to the output.
+fn cache_listed_ids(kind: IdKind, pagination: Pagination, rows: &serde_json::Value) {
+    if pagination.limit.is_some() {
+        return;
+    }
+    let profile = Config::default().profile;
+    if let Err(e) = completions::cache_ids(&profile, kind, rows) {
+        debug!("Failed to cache {kind:?} ids: {e}");
+    }
+}
+
+pub fn handle_cli_status_command(json: &Option<bool>, warn_days: i64) {
+    let whoami_command = WhoamiCommand::new(get_authentication());
+    let whoami = match whoami_command.whoami(chrono::Duration::days(warn_days)) {
+        Ok(whoami) => whoami,
+        Err(e) => {
+            error!("Error occurred: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    match whoami.format_with(&format_options(json, &ListArgs::default())) {
+        Ok(output) => println!("{output}"),
+        Err(e) => {
+            error!("{e}");
+            std::process::exit(1);
+        }
+    }
+
+    let details = &whoami.value[0];
+    match details["status"].as_str().unwrap_or_default() {
+        TOKEN_EXPIRING => warn!(
+            "The API token expires at {}. Please create a new one and run `screenly login`.",
+            details["expires_at"].as_str().unwrap_or_default()
+        ),
+        TOKEN_EXPIRED => {
+            error!("The API token has expired. Please create a new one and run `screenly login`.");
+            std::process::exit(1);
+        }
+        TOKEN_REJECTED => {
+            error!("The API token was rejected. Please run `screenly login` to authenticate.");
+            std::process::exit(1);
+        }
+        _ => {}
     }
 }
 
@@ -628,21 +1433,174 @@ fn get_user_input() -> String {
     user_input.trim().to_string()
 }
 
+pub fn handle_cli_profile_command(command: &ProfileCommands) {
+    let profile_command = ProfileCommand::new();
+
+    match command {
+        ProfileCommands::List { json, list } => {
+            handle_list_execution_result(profile_command.list(), json, list);
+        }
+        ProfileCommands::Use { name } => match profile_command.use_profile(name) {
+            Ok(()) => {
+                info!("Profile \"{name}\" is now active.");
+            }
+            Err(e) => {
+                error!("Error occurred: {e}");
+                std::process::exit(1);
+            }
+        },
+        ProfileCommands::Remove { name } => match profile_command.remove(name) {
+            Ok(()) => {
+                info!("Profile \"{name}\" removed.");
+            }
+            Err(e) => {
+                error!("Error occurred: {e}");
+                std::process::exit(1);
+            }
+        },
+        ProfileCommands::Migrate { backend } => match profile_command.migrate(*backend) {
+            Ok(migrated) if migrated.is_empty() => {
+                info!("No plaintext tokens found. The credential store setting has been saved.");
+            }
+            Ok(migrated) => {
+                info!("Migrated tokens of profiles: {}.", migrated.join(", "));
+            }
+            Err(e) => {
+                error!("Error occurred: {e}");
+                std::process::exit(1);
+            }
+        },
+    }
+}
+
 pub fn handle_cli_screen_command(command: &ScreenCommands) {
     let authentication = get_authentication();
     let screen_command = commands::screen::ScreenCommand::new(authentication);
 
     match command {
-        ScreenCommands::List { json } => {
-            handle_command_execution_result(screen_command.list(), json);
+        ScreenCommands::List {
+            json,
+            label,
+            pagination,
+            list,
+        } => {
+            let result = match label {
+                Some(label) => screen_command.list_with_label(label, pagination.pagination()),
+                None => {
+                    return handle_paged_list(
+                        screen_command.pages(pagination.pagination()),
+                        Screens::new,
+                        |rows| cache_listed_ids(IdKind::Screen, pagination.pagination(), rows),
+                        json,
+                        list,
+                    )
+                }
+            };
+            handle_list_execution_result(result, json, list);
         }
         ScreenCommands::Get { uuid, json } => {
-            handle_command_execution_result(screen_command.get(uuid), json);
+            let id = &resolve_or_exit(screen_command.resolve(uuid));
+            let result = retry_id_as_name(
+                screen_command.get(id),
+                || screen_command.resolve_id_as_name(uuid),
+                |id| screen_command.get(id),
+            );
+            handle_command_execution_result(result, json);
         }
         ScreenCommands::Add { pin, name, json } => {
             handle_command_execution_result(screen_command.add(pin, name.clone()), json);
         }
+        ScreenCommands::Import { json, path, jobs } => {
+            let report = inventory::read_inventory(path)
+                .and_then(|rows| screen_command.import(&rows, *jobs));
+            let failed = report.as_ref().map_or(0, ImportReport::failed);
+            handle_command_execution_result(report, json);
+            if failed > 0 {
+                std::process::exit(1);
+            }
+        }
+        ScreenCommands::Playlists {
+            json,
+            uuid,
+            playing,
+            list,
+        } => {
+            let uuid = &resolve_or_exit(screen_command.resolve(uuid));
+            handle_list_execution_result(screen_command.playlists(uuid, *playing), json, list);
+        }
+        ScreenCommands::Status {
+            json,
+            label,
+            watch,
+            exit_code,
+            list,
+        } => {
+            let status = || screen_command.status(label.as_deref());
+            let options = format_options(json, list);
+            // Other formats are read by programs, which expect nothing but the rows.
+            let table =
+                options.output_type == OutputType::HumanReadable && options.template.is_none();
+            match watch {
+                Some(interval) => loop {
+                    match status().and_then(|statuses| statuses.format_with(&options)) {
+                        Ok(output) => {
+                            if table {
+                                // Clears the terminal so the summary stays in place.
+                                print!("\x1b[2J\x1b[H");
+                                println!(
+                                    "Every {interval}s, last refreshed {}\n",
+                                    chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+                                );
+                            }
+                            println!("{output}");
+                        }
+                        // Rendering fails the same way on every refresh.
+                        Err(e @ CommandError::Template(_)) => {
+                            error!("{e}");
+                            std::process::exit(1);
+                        }
+                        // A failed refresh should not end the watch, the next one may succeed.
+                        Err(e) => error!("Error occurred: {e}"),
+                    }
+                    std::thread::sleep(std::time::Duration::from_secs(*interval));
+                },
+                None => {
+                    let result = status();
+                    let offline = match &result {
+                        Ok(statuses) if *exit_code => statuses.offline(&options),
+                        _ => 0,
+                    };
+                    handle_list_execution_result(result, json, list);
+                    if offline > 0 {
+                        std::process::exit(2);
+                    }
+                }
+            }
+        }
+        ScreenCommands::Update {
+            json,
+            uuid,
+            name,
+            location,
+            coordinates,
+            notes,
+        } => {
+            let id = &resolve_or_exit(screen_command.resolve(uuid));
+            let update = ScreenUpdate {
+                name: name.clone(),
+                location: location.clone(),
+                coordinates: *coordinates,
+                notes: notes.clone(),
+            };
+            let result = retry_id_as_name(
+                screen_command.update(id, &update),
+                || screen_command.resolve_id_as_name(uuid),
+                |id| screen_command.update(id, &update),
+            );
+            handle_command_execution_result(result, json);
+        }
         ScreenCommands::Delete { uuid } => {
+            let uuid = &resolve_or_exit(screen_command.resolve(uuid));
             match get_screen_name(uuid, &screen_command) {
                 Ok(name) => {
                     info!("You are about to delete the screen named \"{name}\".  This operation cannot be reversed.");
@@ -679,16 +1637,29 @@ pub fn handle_cli_playlist_command(command: &PlaylistCommands) {
             json,
             title,
             predicate,
+            schedule,
         } => {
+            let predicate = predicate_or_exit(predicate, schedule);
             handle_command_execution_result(
-                playlist_command.create(title, &predicate.clone().unwrap_or("TRUE".to_owned())),
+                playlist_command.create(title, &predicate.unwrap_or("TRUE".to_owned())),
                 json,
             );
         }
-        PlaylistCommands::List { json } => {
-            handle_command_execution_result(playlist_command.list(), json);
+        PlaylistCommands::List {
+            json,
+            pagination,
+            list,
+        } => {
+            handle_paged_list(
+                playlist_command.pages(pagination.pagination()),
+                Playlists::new,
+                |rows| cache_listed_ids(IdKind::Playlist, pagination.pagination(), rows),
+                json,
+                list,
+            );
         }
         PlaylistCommands::Get { uuid } => {
+            let uuid = &resolve_or_exit(playlist_command.resolve(uuid));
             let playlist_file = playlist_command.get_playlist_file(uuid);
             match playlist_file {
                 Ok(playlist) => {
@@ -700,20 +1671,25 @@ pub fn handle_cli_playlist_command(command: &PlaylistCommands) {
                 }
             }
         }
-        PlaylistCommands::Delete { uuid } => match playlist_command.delete(uuid) {
-            Ok(()) => {
-                println!("Playlist deleted successfully.");
-            }
-            Err(e) => {
-                eprintln!("Error occurred when deleting playlist: {e:?}")
+        PlaylistCommands::Delete { uuid } => {
+            let uuid = &resolve_or_exit(playlist_command.resolve(uuid));
+            match playlist_command.delete(uuid) {
+                Ok(()) => {
+                    println!("Playlist deleted successfully.");
+                }
+                Err(e) => {
+                    eprintln!("Error occurred when deleting playlist: {e:?}")
+                }
             }
-        },
+        }
         PlaylistCommands::Append {
             json,
             uuid,
             asset_uuid,
             duration,
         } => {
+            let uuid = &resolve_or_exit(playlist_command.resolve(uuid));
+            let asset_uuid = &resolve_or_exit(playlist_command.resolve_asset(asset_uuid));
             handle_command_execution_result(
                 playlist_command.append_asset(
                     uuid,
@@ -729,6 +1705,8 @@ pub fn handle_cli_playlist_command(command: &PlaylistCommands) {
             asset_uuid,
             duration,
         } => {
+            let uuid = &resolve_or_exit(playlist_command.resolve(uuid));
+            let asset_uuid = &resolve_or_exit(playlist_command.resolve_asset(asset_uuid));
             handle_command_execution_result(
                 playlist_command.prepend_asset(
                     uuid,
@@ -738,7 +1716,18 @@ pub fn handle_cli_playlist_command(command: &PlaylistCommands) {
                 json,
             );
         }
-        PlaylistCommands::Update {} => {
+        PlaylistCommands::Update {
+            json,
+            uuid: Some(uuid),
+            predicate,
+            schedule,
+        } => {
+            let uuid = &resolve_or_exit(playlist_command.resolve(uuid));
+            // clap requires a predicate or schedule option along with the playlist.
+            let predicate = predicate_or_exit(predicate, schedule).unwrap_or_default();
+            handle_command_execution_result(playlist_command.set_predicate(uuid, &predicate), json);
+        }
+        PlaylistCommands::Update { uuid: None, .. } => {
             let mut input = String::new();
             io::stdin()
                 .read_to_string(&mut input)
@@ -751,9 +1740,243 @@ pub fn handle_cli_playlist_command(command: &PlaylistCommands) {
                     println!("Playlist updated successfully.");
                 }
                 Err(e) => {
-                    eprintln!("Error occurred when updating playlist: {e:?}")
+                    error!("Error occurred when updating playlist: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        PlaylistCommands::Explain { uuid } => {
+            let uuid = &resolve_or_exit(playlist_command.resolve(uuid));
+            match playlist_command.explain(uuid) {
+                Ok(explanation) => println!("{explanation}"),
+                Err(e) => {
+                    error!("{e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        PlaylistCommands::Share { json, uuid, team } => {
+            let uuid = &resolve_or_exit(playlist_command.resolve(uuid));
+            let team = &resolve_or_exit(playlist_command.resolve_team(team));
+            handle_command_execution_result(playlist_command.share(uuid, team), json);
+        }
+        PlaylistCommands::Unshare { uuid, team } => {
+            let uuid = &resolve_or_exit(playlist_command.resolve(uuid));
+            let team = &resolve_or_exit(playlist_command.resolve_team(team));
+            exit_on_error(
+                playlist_command.unshare(uuid, team),
+                "Playlist is no longer shared with the team.",
+            );
+        }
+        PlaylistCommands::Shares {
+            json,
+            uuid,
+            pagination,
+            list,
+        } => {
+            let uuid = uuid
+                .as_ref()
+                .map(|uuid| resolve_or_exit(playlist_command.resolve(uuid)));
+            handle_list_execution_result(
+                playlist_command.shares(uuid.as_deref(), pagination.pagination()),
+                json,
+                list,
+            );
+        }
+        PlaylistCommands::Assign {
+            uuid,
+            screen,
+            label,
+        } => {
+            let uuid = &resolve_or_exit(playlist_command.resolve(uuid));
+            let result = match screen {
+                Some(screen) => {
+                    let screen = &resolve_or_exit(playlist_command.resolve_screen(screen));
+                    playlist_command.assign_screen(uuid, screen)
+                }
+                // clap requires --label when --screen is missing.
+                None => {
+                    let label = label.as_deref().unwrap_or_default();
+                    let label = &resolve_or_exit(playlist_command.resolve_label(label));
+                    playlist_command.assign_label(uuid, label)
+                }
+            };
+            exit_on_error(result, "Playlist assigned.");
+        }
+        PlaylistCommands::Unassign {
+            uuid,
+            screen,
+            label,
+        } => {
+            let uuid = &resolve_or_exit(playlist_command.resolve(uuid));
+            let result = match screen {
+                Some(screen) => {
+                    let screen = &resolve_or_exit(playlist_command.resolve_screen(screen));
+                    playlist_command.unassign_screen(uuid, screen)
+                }
+                // clap requires --label when --screen is missing.
+                None => {
+                    let label = label.as_deref().unwrap_or_default();
+                    let label = &resolve_or_exit(playlist_command.resolve_label(label));
+                    playlist_command.unassign_label(uuid, label)
                 }
+            };
+            exit_on_error(result, "Playlist unassigned.");
+        }
+        PlaylistCommands::Simulate {
+            json,
+            uuid,
+            predicate,
+            from,
+            to,
+            tz,
+            ical,
+        } => {
+            let from = from.unwrap_or_else(|| chrono::Utc::now().with_timezone(&tz.0).date_naive());
+            let to = to.unwrap_or(from + chrono::Days::new(6));
+            let result = match predicate {
+                Some(predicate) => schedule::simulate(predicate, from, to, *tz, predicate),
+                // clap requires a playlist when --predicate is missing.
+                None => {
+                    let uuid = uuid.as_deref().unwrap_or_default();
+                    let uuid = &resolve_or_exit(playlist_command.resolve(uuid));
+                    playlist_command.simulate(uuid, from, to, *tz)
+                }
+            };
+            match result {
+                Ok(intervals) if *ical => print!("{}", intervals.ical()),
+                result => handle_command_execution_result(result, json),
+            }
+        }
+    }
+}
+
+pub fn handle_cli_asset_group_command(command: &AssetGroupCommands) {
+    let asset_group_command = AssetGroupCommand::new(get_authentication());
+
+    match command {
+        AssetGroupCommands::List {
+            json,
+            pagination,
+            list,
+        } => {
+            handle_list_execution_result(
+                asset_group_command.list(pagination.pagination()),
+                json,
+                list,
+            );
+        }
+        AssetGroupCommands::Create { json, title } => {
+            handle_command_execution_result(asset_group_command.create(title), json);
+        }
+        AssetGroupCommands::Update { json, uuid, title } => {
+            let id = &resolve_or_exit(asset_group_command.resolve(uuid));
+            let result = retry_id_as_name(
+                asset_group_command.update(id, title),
+                || asset_group_command.resolve_id_as_name(uuid),
+                |id| asset_group_command.update(id, title),
+            );
+            handle_command_execution_result(result, json);
+        }
+        AssetGroupCommands::Delete { uuid } => {
+            let uuid = &resolve_or_exit(asset_group_command.resolve(uuid));
+            let title = asset_group_command
+                .get(uuid)
+                .ok()
+                .and_then(|groups| groups.value[0]["title"].as_str().map(str::to_string));
+            let Some(title) = title else {
+                error!("Asset group could not be found.");
+                std::process::exit(1);
+            };
+            info!("You are about to delete the asset group named \"{title}\" and all assets in it.  This operation cannot be reversed.");
+            info!("Enter the asset group title to confirm the deletion: ");
+            if title != get_user_input() {
+                error!("The title you entered is incorrect. Aborting.");
+                std::process::exit(1);
             }
+            exit_on_error(
+                asset_group_command.delete(uuid),
+                "Asset group deleted successfully.",
+            );
+        }
+    }
+}
+
+pub fn handle_cli_label_command(command: &LabelCommands) {
+    let label_command = LabelCommand::new(get_authentication());
+
+    match command {
+        LabelCommands::List {
+            json,
+            pagination,
+            list,
+        } => {
+            handle_paged_list(
+                label_command.pages(pagination.pagination()),
+                Labels::new,
+                |_| {},
+                json,
+                list,
+            );
+        }
+        LabelCommands::Create { json, name } => {
+            handle_command_execution_result(label_command.create(name), json);
+        }
+        LabelCommands::Update { json, uuid, name } => {
+            let id = &resolve_or_exit(label_command.resolve(uuid));
+            let result = retry_id_as_name(
+                label_command.update(id, name),
+                || label_command.resolve_id_as_name(uuid),
+                |id| label_command.update(id, name),
+            );
+            handle_command_execution_result(result, json);
+        }
+        LabelCommands::Delete { uuid } => {
+            let uuid = &resolve_or_exit(label_command.resolve(uuid));
+            exit_on_error(label_command.delete(uuid), "Label deleted successfully.");
+        }
+        LabelCommands::LinkScreen { uuid, screen } => {
+            let uuid = &resolve_or_exit(label_command.resolve(uuid));
+            let screen = &resolve_or_exit(label_command.resolve_screen(screen));
+            exit_on_error(
+                label_command.link_screen(uuid, screen),
+                "Label added to the screen.",
+            );
+        }
+        LabelCommands::UnlinkScreen { uuid, screen } => {
+            let uuid = &resolve_or_exit(label_command.resolve(uuid));
+            let screen = &resolve_or_exit(label_command.resolve_screen(screen));
+            exit_on_error(
+                label_command.unlink_screen(uuid, screen),
+                "Label removed from the screen.",
+            );
+        }
+        LabelCommands::LinkPlaylist { uuid, playlist } => {
+            let uuid = &resolve_or_exit(label_command.resolve(uuid));
+            let playlist = &resolve_or_exit(label_command.resolve_playlist(playlist));
+            exit_on_error(
+                label_command.link_playlist(uuid, playlist),
+                "Label added to the playlist.",
+            );
+        }
+        LabelCommands::UnlinkPlaylist { uuid, playlist } => {
+            let uuid = &resolve_or_exit(label_command.resolve(uuid));
+            let playlist = &resolve_or_exit(label_command.resolve_playlist(playlist));
+            exit_on_error(
+                label_command.unlink_playlist(uuid, playlist),
+                "Label removed from the playlist.",
+            );
+        }
+    }
+}
+
+// Logs `message` on success, and the error followed by exit code 1 otherwise.
+fn exit_on_error(result: Result<(), CommandError>, message: &str) {
+    match result {
+        Ok(()) => info!("{message}"),
+        Err(e) => {
+            error!("Error occurred: {e}");
+            std::process::exit(1);
         }
     }
 }
@@ -763,16 +1986,56 @@ pub fn handle_cli_asset_command(command: &AssetCommands) {
     let asset_command = commands::asset::AssetCommand::new(authentication);
 
     match command {
-        AssetCommands::List { json } => {
-            handle_command_execution_result(asset_command.list(), json);
+        AssetCommands::List {
+            json,
+            pagination,
+            list,
+        } => {
+            handle_paged_list(
+                asset_command.pages(pagination.pagination()),
+                Assets::new,
+                |rows| cache_listed_ids(IdKind::Asset, pagination.pagination(), rows),
+                json,
+                list,
+            );
         }
         AssetCommands::Get { uuid, json } => {
-            handle_command_execution_result(asset_command.get(uuid), json);
+            let id = &resolve_or_exit(asset_command.resolve(uuid));
+            let result = retry_id_as_name(
+                asset_command.get(id),
+                || asset_command.resolve_id_as_name(uuid),
+                |id| asset_command.get(id),
+            );
+            handle_command_execution_result(result, json);
         }
-        AssetCommands::Add { path, title, json } => {
-            handle_command_execution_result(asset_command.add(path, title), json);
+        AssetCommands::Add {
+            path,
+            title,
+            group,
+            json,
+        } => {
+            let group = group
+                .as_ref()
+                .map(|group| resolve_or_exit(asset_command.resolve_group(group)));
+            handle_command_execution_result(asset_command.add(path, title, group.as_deref()), json);
+        }
+        AssetCommands::Move {
+            json,
+            uuid,
+            group,
+            no_group: _,
+        } => {
+            let uuid = &resolve_or_exit(asset_command.resolve(uuid));
+            let group = group
+                .as_ref()
+                .map(|group| resolve_or_exit(asset_command.resolve_group(group)));
+            handle_command_execution_result(
+                asset_command.move_to_group(uuid, group.as_deref()),
+                json,
+            );
         }
         AssetCommands::Delete { uuid } => {
+            let uuid = &resolve_or_exit(asset_command.resolve(uuid));
             match get_asset_title(uuid, &asset_command) {
                 Ok(title) => {
                     info!("You are about to delete the asset named \"{title}\".  This operation cannot be reversed.");
@@ -811,6 +2074,7 @@ pub fn handle_cli_asset_command(command: &AssetCommands) {
             }
         }
         AssetCommands::InjectJs { uuid, path } => {
+            let uuid = &resolve_or_exit(asset_command.resolve(uuid));
             let js_code = if path.starts_with("http://") || path.starts_with("https://") {
                 match reqwest::blocking::get(path) {
                     Ok(response) => match response.status() {
@@ -846,6 +2110,7 @@ pub fn handle_cli_asset_command(command: &AssetCommands) {
             }
         }
         AssetCommands::SetHeaders { uuid, headers } => {
+            let uuid = &resolve_or_exit(asset_command.resolve(uuid));
             match asset_command.set_web_asset_headers(uuid, headers.headers.clone()) {
                 Ok(()) => {
                     info!("Asset updated successfully.");
@@ -857,6 +2122,7 @@ pub fn handle_cli_asset_command(command: &AssetCommands) {
             }
         }
         AssetCommands::BasicAuth { uuid, credentials } => {
+            let uuid = &resolve_or_exit(asset_command.resolve(uuid));
             let basic_auth = Credentials::new(&credentials.0, &credentials.1);
             match asset_command.update_web_asset_headers(
                 uuid,
@@ -872,6 +2138,7 @@ pub fn handle_cli_asset_command(command: &AssetCommands) {
             }
         }
         AssetCommands::UpdateHeaders { uuid, headers } => {
+            let uuid = &resolve_or_exit(asset_command.resolve(uuid));
             match asset_command.update_web_asset_headers(uuid, headers.headers.clone()) {
                 Ok(()) => {
                     info!("Asset updated successfully.");
@@ -883,6 +2150,7 @@ pub fn handle_cli_asset_command(command: &AssetCommands) {
             }
         }
         AssetCommands::BearerAuth { uuid, token } => {
+            let uuid = &resolve_or_exit(asset_command.resolve(uuid));
             match asset_command.update_web_asset_headers(
                 uuid,
                 vec![("Authorization".to_owned(), format!("Bearer {token}"))],
@@ -934,8 +2202,8 @@ pub fn handle_cli_edge_app_command(command: &EdgeAppCommands) {
             }
         }
 
-        EdgeAppCommands::List { json } => {
-            handle_command_execution_result(edge_app_command.list(), json);
+        EdgeAppCommands::List { json, list } => {
+            handle_list_execution_result(edge_app_command.list(), json, list);
         }
         EdgeAppCommands::Deploy {
             path,
@@ -950,8 +2218,12 @@ pub fn handle_cli_edge_app_command(command: &EdgeAppCommands) {
             }
         },
         EdgeAppCommands::Setting(command) => match command {
-            EdgeAppSettingsCommands::List { path, json } => {
-                handle_command_execution_result(edge_app_command.list_settings(path.clone()), json);
+            EdgeAppSettingsCommands::List { path, json, list } => {
+                handle_list_execution_result(
+                    edge_app_command.list_settings(path.clone()),
+                    json,
+                    list,
+                );
             }
             EdgeAppSettingsCommands::Set { setting_pair, path } => {
                 match edge_app_command.set_setting(path.clone(), &setting_pair.0, &setting_pair.1) {
@@ -1143,7 +2415,7 @@ pub fn handle_cli_edge_app_command(command: &EdgeAppCommands) {
             }
         }
         EdgeAppCommands::Instance(command) => match command {
-            EdgeAppInstanceCommands::List { path, json } => {
+            EdgeAppInstanceCommands::List { path, json, list } => {
                 let actual_app_id = match edge_app_command.get_app_id(path.clone()) {
                     Ok(id) => id,
                     Err(e) => {
@@ -1151,9 +2423,10 @@ pub fn handle_cli_edge_app_command(command: &EdgeAppCommands) {
                         std::process::exit(1);
                     }
                 };
-                handle_command_execution_result(
+                handle_list_execution_result(
                     edge_app_command.list_instances(&actual_app_id),
                     json,
+                    list,
                 );
             }
             EdgeAppInstanceCommands::Create { path, name } => {
@@ -1303,6 +2576,40 @@ mod tests {
         assert_eq!(new_path, dir_path.join("screenly.yml"));
     }
 
+    #[test]
+    fn test_format_error_json_should_include_api_error_fields() {
+        let e = CommandError::Api(Box::new(commands::ApiError {
+            status: 409,
+            method: "POST".to_string(),
+            url: "https://api.screenlyapp.com/v4/screens".to_string(),
+            message: Some("duplicate key value".to_string()),
+            code: Some("23505".to_string()),
+            ..Default::default()
+        }));
+
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&format_error_json(&e)).unwrap(),
+            serde_json::json!({
+                "error": {
+                    "status": 409,
+                    "method": "POST",
+                    "url": "https://api.screenlyapp.com/v4/screens",
+                    "message": "duplicate key value",
+                    "code": "23505"
+                }
+            })
+        );
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&format_error_json(
+                &CommandError::MissingField
+            ))
+            .unwrap(),
+            serde_json::json!({
+                "error": { "message": "Required field is missing in the response" }
+            })
+        );
+    }
+
     #[test]
     fn test_get_authentication_error_message_when_not_logged_in() {
         let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "file not found");
@@ -1326,4 +2633,49 @@ mod tests {
         assert!(message.contains("Authentication error"));
         assert!(message.contains("Please run `screenly login` to authenticate"));
     }
+
+    #[test]
+    fn test_retry_id_as_name_when_no_screen_has_id_should_get_screen_named_like_it() {
+        let mock_server = MockServer::start();
+        let id_mock = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/screens")
+                .query_param("id", "eq.BREAKFAST2026WEEKDAYSMENVS");
+            then.status(200).json_body(serde_json::json!([]));
+        });
+        let name_mock = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/screens")
+                .query_param("name", "eq.BREAKFAST2026WEEKDAYSMENVS");
+            then.status(200).json_body(serde_json::json!([
+                {"id": "01H2QZ6Z8WXWNDC0KQ198XCZEW", "name": "BREAKFAST2026WEEKDAYSMENVS"}
+            ]));
+        });
+        let screen_mock = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/screens")
+                .query_param("id", "eq.01H2QZ6Z8WXWNDC0KQ198XCZEW");
+            then.status(200).json_body(serde_json::json!([
+                {"id": "01H2QZ6Z8WXWNDC0KQ198XCZEW", "name": "BREAKFAST2026WEEKDAYSMENVS"}
+            ]));
+        });
+
+        let config = Config::new(mock_server.base_url());
+        let authentication = Authentication::new_with_config(config, "token");
+        let screen_command = commands::screen::ScreenCommand::new(authentication);
+        let reference = "BREAKFAST2026WEEKDAYSMENVS";
+        let id = screen_command.resolve(reference).unwrap();
+        assert_eq!(id, reference);
+        let screens = retry_id_as_name(
+            screen_command.get(&id),
+            || screen_command.resolve_id_as_name(reference),
+            |id| screen_command.get(id),
+        )
+        .unwrap();
+
+        assert_eq!(screens.value[0]["id"], "01H2QZ6Z8WXWNDC0KQ198XCZEW");
+        id_mock.assert();
+        name_mock.assert();
+        screen_mock.assert();
+    }
 }
@@ -5,12 +5,15 @@ use std::time::Duration;
 use indicatif::{ProgressBar, ProgressStyle};
 use log::{debug, info};
 use reqwest::header::HeaderMap;
-use reqwest::StatusCode;
+use reqwest::{Method, StatusCode};
 use serde_json::json;
 
 use crate::authentication::Authentication;
 use crate::commands;
-use crate::commands::{Assets, CommandError};
+use crate::commands::lookup::{self, Resource};
+use crate::commands::pagination::{Pages, Pagination};
+use crate::commands::retry::send_with_retry;
+use crate::commands::{ApiError, Assets, CommandError};
 
 pub struct AssetCommand {
     authentication: Authentication,
@@ -21,11 +24,28 @@ impl AssetCommand {
         Self { authentication }
     }
 
-    pub fn list(&self) -> anyhow::Result<Assets, CommandError> {
-        Ok(Assets::new(commands::get(
+    /// The rows of the list command, one page at a time.
+    pub fn pages(&self, pagination: Pagination) -> Pages<'_> {
+        Pages::new(
             &self.authentication,
             "v4/assets?type=neq.edge-app-file",
-        )?))
+            pagination,
+        )
+    }
+
+    /// Resolves an asset id, title or `name:<glob>` selector to an id.
+    pub fn resolve(&self, reference: &str) -> Result<String, CommandError> {
+        lookup::resolve(&self.authentication, Resource::Asset, reference)
+    }
+
+    /// Looks up an id-shaped reference as an asset name, for when nothing has that id.
+    pub fn resolve_id_as_name(&self, reference: &str) -> Result<Option<String>, CommandError> {
+        lookup::resolve_id_as_name(&self.authentication, Resource::Asset, reference)
+    }
+
+    /// Resolves an asset group id, title or `name:<glob>` selector to an id.
+    pub fn resolve_group(&self, reference: &str) -> Result<String, CommandError> {
+        lookup::resolve(&self.authentication, Resource::AssetGroup, reference)
     }
 
     pub fn get(&self, id: &str) -> anyhow::Result<Assets, CommandError> {
@@ -40,23 +60,30 @@ impl AssetCommand {
         headers: &HeaderMap,
         payload: &HashMap<&str, &str>,
     ) -> anyhow::Result<Assets, CommandError> {
-        let response = self
-            .authentication
-            .build_client()?
-            .post(url)
-            .json(payload)
-            .headers(headers.clone())
-            .send()?;
+        if self.authentication.config.dry_run {
+            let planned = commands::dry_run(Method::POST, url, Some(json!(payload)))?;
+            return Ok(Assets::new(planned));
+        }
+
+        let client = self.authentication.build_client()?;
+        let response = send_with_retry(&self.authentication, false, || {
+            Ok(client.post(url).json(payload).headers(headers.clone()))
+        })?;
 
         if response.status() != StatusCode::CREATED {
-            let status = response.status().as_u16();
-            return Err(CommandError::WrongResponseStatus(status));
+            return Err(ApiError::from_response(Method::POST, response).into());
         }
 
         Ok(Assets::new(serde_json::from_str(&response.text()?)?))
     }
 
-    pub fn add(&self, path: &str, title: &str) -> anyhow::Result<Assets, CommandError> {
+    /// Uploads a file or adds a web asset. `group_id` puts the asset into an asset group.
+    pub fn add(
+        &self,
+        path: &str,
+        title: &str,
+        group_id: Option<&str>,
+    ) -> anyhow::Result<Assets, CommandError> {
         let url = format!("{}/v4/assets", &self.authentication.config.url);
 
         let mut headers = HeaderMap::new();
@@ -66,11 +93,22 @@ impl AssetCommand {
             let mut payload = HashMap::new();
             payload.insert("title", title);
             payload.insert("source_url", path);
+            if let Some(group_id) = group_id {
+                payload.insert("asset_group_id", group_id);
+            }
             return self.add_web_asset(&url, &headers, &payload);
         }
 
-        let file = File::open(path)?;
-        let file_size = file.metadata()?.len();
+        let file_size = File::open(path)?.metadata()?.len();
+        if self.authentication.config.dry_run {
+            let mut planned = json!({ "title": title, "file": path, "size": file_size });
+            if let Some(group_id) = group_id {
+                planned["asset_group_id"] = json!(group_id);
+            }
+            let planned = commands::dry_run(Method::POST, &url, Some(planned))?;
+            return Ok(Assets::new(planned));
+        }
+
         let pb = ProgressBar::new(file_size);
         info!("Uploading asset.");
         if let Ok(template) = ProgressStyle::with_template(
@@ -79,23 +117,29 @@ impl AssetCommand {
             pb.set_style(template);
         }
 
-        let part = reqwest::blocking::multipart::Part::reader(pb.wrap_read(file)).file_name("file");
-        let form = reqwest::blocking::multipart::Form::new()
-            .text("title", title.to_owned())
-            .part("file", part);
+        let client = self.authentication.build_client()?;
+        let response = send_with_retry(&self.authentication, false, || {
+            // The body is streamed from the file, so every attempt reopens it.
+            let file = File::open(path)?;
+            pb.set_position(0);
+            let part =
+                reqwest::blocking::multipart::Part::reader(pb.wrap_read(file)).file_name("file");
+            let mut form =
+                reqwest::blocking::multipart::Form::new().text("title", title.to_owned());
+            if let Some(group_id) = group_id {
+                form = form.text("asset_group_id", group_id.to_owned());
+            }
+            let form = form.part("file", part);
 
-        let response = self
-            .authentication
-            .build_client()?
-            .post(url)
-            .multipart(form)
-            .headers(headers)
-            .timeout(Duration::from_secs(3600)) // timeout is equal to server timeout
-            .send()?;
+            Ok(client
+                .post(&url)
+                .multipart(form)
+                .headers(headers.clone())
+                .timeout(Duration::from_secs(3600))) // timeout is equal to server timeout
+        })?;
 
         if response.status() != StatusCode::CREATED {
-            let status = response.status().as_u16();
-            return Err(CommandError::WrongResponseStatus(status));
+            return Err(ApiError::from_response(Method::POST, response).into());
         }
 
         Ok(Assets::new(serde_json::from_str(&response.text()?)?))
@@ -152,6 +196,27 @@ impl AssetCommand {
         Ok(())
     }
 
+    /// Moves an asset into an asset group, or out of any group when `group_id` is `None`.
+    pub fn move_to_group(
+        &self,
+        id: &str,
+        group_id: Option<&str>,
+    ) -> anyhow::Result<Assets, CommandError> {
+        let endpoint = format!("v4/assets?id=eq.{id}");
+        let response = commands::patch(
+            &self.authentication,
+            &endpoint,
+            &json!({ "asset_group_id": group_id }),
+        )?;
+        if response.as_array().is_some_and(|assets| assets.is_empty()) {
+            return Err(CommandError::ResourceNotFound(
+                "asset".to_string(),
+                id.to_string(),
+            ));
+        }
+        Ok(Assets::new(response))
+    }
+
     pub fn delete(&self, id: &str) -> anyhow::Result<(), CommandError> {
         let endpoint = format!("v4/assets?id=eq.{id}");
         commands::delete(&self.authentication, &endpoint)
@@ -168,7 +233,7 @@ mod tests {
 
     use super::*;
     use crate::authentication::Config;
-    use crate::commands::{Formatter, OutputType};
+    use crate::commands::{FormatOptions, Formatter, OutputType};
 
     #[test]
     fn test_list_assets_should_return_correct_asset_list() {
@@ -227,8 +292,8 @@ mod tests {
         let config = Config::new(mock_server.base_url());
         let authentication = Authentication::new_with_config(config, "token");
         let asset_command = AssetCommand::new(authentication);
-        let v = asset_command.list().unwrap();
-        assert_eq!(v.value, asset_list);
+        let v = asset_command.pages(Pagination::default()).rows().unwrap();
+        assert_eq!(v, asset_list);
     }
 
     #[test]
@@ -275,13 +340,125 @@ mod tests {
         let config = Config::new(mock_server.base_url());
         let authentication = Authentication::new_with_config(config, "token");
         let asset_command = AssetCommand::new(authentication);
-        let v = asset_command.add(tmp_dir.path().join("1.html").to_str().unwrap(), "test");
+        let v = asset_command.add(
+            tmp_dir.path().join("1.html").to_str().unwrap(),
+            "test",
+            None,
+        );
         post_mock.assert();
 
         assert!(v.is_ok());
         assert_eq!(v.unwrap().value, new_asset);
     }
 
+    #[test]
+    fn test_add_asset_when_rate_limited_should_retry_upload() {
+        let tmp_dir = tempdir().unwrap();
+        fs::write(tmp_dir.path().join("1.html").to_str().unwrap(), "dummy").unwrap();
+
+        let mock_server = MockServer::start();
+        let post_mock = mock_server.mock(|when, then| {
+            when.method(POST).path("/v4/assets");
+            then.status(429).header("Retry-After", "0");
+        });
+
+        let config = Config::new(mock_server.base_url());
+        let authentication = Authentication::new_with_config(config, "token");
+        let asset_command = AssetCommand::new(authentication);
+        let v = asset_command.add(
+            tmp_dir.path().join("1.html").to_str().unwrap(),
+            "test",
+            None,
+        );
+
+        post_mock.assert_calls(4);
+        assert_eq!(v.unwrap_err().status(), Some(429));
+    }
+
+    #[test]
+    fn test_add_asset_in_dry_run_should_not_upload_file() {
+        let tmp_dir = tempdir().unwrap();
+        fs::write(tmp_dir.path().join("1.html").to_str().unwrap(), "dummy").unwrap();
+
+        let mock_server = MockServer::start();
+        let post_mock = mock_server.mock(|when, then| {
+            when.method(POST).path("/v4/assets");
+            then.status(201);
+        });
+
+        let mut config = Config::new(mock_server.base_url());
+        config.dry_run = true;
+        let authentication = Authentication::new_with_config(config, "token");
+        let asset_command = AssetCommand::new(authentication);
+        let path = tmp_dir.path().join("1.html");
+        let v = asset_command
+            .add(path.to_str().unwrap(), "test", None)
+            .unwrap();
+
+        post_mock.assert_calls(0);
+        assert_eq!(
+            v.value,
+            json!([{ "title": "test", "file": path.to_str().unwrap(), "size": 5 }])
+        );
+    }
+
+    #[test]
+    fn test_add_web_asset_with_group_should_send_group_id() {
+        let mock_server = MockServer::start();
+        let post_mock = mock_server.mock(|when, then| {
+            when.method(POST).path("/v4/assets").json_body(json!({
+                "source_url": "https://google.com",
+                "title": "test",
+                "asset_group_id": "01H2QZ6Z8WXWNDC0KQ198XCZEG"
+            }));
+            then.status(201).json_body(json!([{"id": "asset"}]));
+        });
+
+        let config = Config::new(mock_server.base_url());
+        let authentication = Authentication::new_with_config(config, "token");
+        let asset_command = AssetCommand::new(authentication);
+        asset_command
+            .add(
+                "https://google.com",
+                "test",
+                Some("01H2QZ6Z8WXWNDC0KQ198XCZEG"),
+            )
+            .unwrap();
+        post_mock.assert();
+    }
+
+    #[test]
+    fn test_move_to_group_should_patch_asset_group_id() {
+        let mock_server = MockServer::start();
+        let move_mock = mock_server.mock(|when, then| {
+            when.method(PATCH)
+                .path("/v4/assets")
+                .query_param("id", "eq.asset")
+                .json_body(json!({"asset_group_id": "01H2QZ6Z8WXWNDC0KQ198XCZEG"}));
+            then.status(200).json_body(
+                json!([{"id": "asset", "asset_group_id": "01H2QZ6Z8WXWNDC0KQ198XCZEG"}]),
+            );
+        });
+        let ungroup_mock = mock_server.mock(|when, then| {
+            when.method(PATCH)
+                .path("/v4/assets")
+                .query_param("id", "eq.asset")
+                .json_body(json!({"asset_group_id": null}));
+            then.status(200)
+                .json_body(json!([{"id": "asset", "asset_group_id": null}]));
+        });
+
+        let config = Config::new(mock_server.base_url());
+        let authentication = Authentication::new_with_config(config, "token");
+        let asset_command = AssetCommand::new(authentication);
+        asset_command
+            .move_to_group("asset", Some("01H2QZ6Z8WXWNDC0KQ198XCZEG"))
+            .unwrap();
+        asset_command.move_to_group("asset", None).unwrap();
+        move_mock.assert();
+        ungroup_mock.assert();
+    }
+
     #[test]
     fn test_add_asset_when_web_asset_should_send_correct_request() {
         let tmp_dir = tempdir().unwrap();
@@ -325,7 +502,7 @@ mod tests {
         let config = Config::new(mock_server.base_url());
         let authentication = Authentication::new_with_config(config, "token");
         let asset_command = AssetCommand::new(authentication);
-        let v = asset_command.add("https://google.com", "test");
+        let v = asset_command.add("https://google.com", "test", None);
         assert!(v.is_ok());
         post_mock.assert();
         assert_eq!(v.unwrap().value, new_asset);
@@ -424,7 +601,12 @@ mod tests {
           }
         ]));
 
-        println!("{}", asset.format(OutputType::HumanReadable));
+        println!(
+            "{}",
+            asset
+                .format_with(&FormatOptions::new(OutputType::HumanReadable))
+                .unwrap()
+        );
         let expected_output =
             "+--------------------------------------+------------+------+--------+\n\
         | Id                                   | Title      | Type | Status |\n\
@@ -432,7 +614,12 @@ mod tests {
         | 0184f162-585e-6334-8dae-38a80062a6c2 | test3.html | N/A  | none   |\n\
         +--------------------------------------+------------+------+--------+\n";
 
-        assert_eq!(asset.format(OutputType::HumanReadable), expected_output);
+        assert_eq!(
+            asset
+                .format_with(&FormatOptions::new(OutputType::HumanReadable))
+                .unwrap(),
+            expected_output
+        );
     }
 
     #[test]
@@ -0,0 +1,167 @@
+use serde_json::json;
+
+use crate::authentication::Authentication;
+use crate::commands;
+use crate::commands::lookup::{self, Resource};
+use crate::commands::pagination::{self, Pagination};
+use crate::commands::{AssetGroups, CommandError};
+
+pub struct AssetGroupCommand {
+    authentication: Authentication,
+}
+
+impl AssetGroupCommand {
+    pub fn new(authentication: Authentication) -> Self {
+        Self { authentication }
+    }
+
+    /// Resolves an asset group id, title or `name:<glob>` selector to an id.
+    pub fn resolve(&self, reference: &str) -> Result<String, CommandError> {
+        lookup::resolve(&self.authentication, Resource::AssetGroup, reference)
+    }
+
+    /// Looks up an id-shaped reference as an asset group name, for when nothing has that id.
+    pub fn resolve_id_as_name(&self, reference: &str) -> Result<Option<String>, CommandError> {
+        lookup::resolve_id_as_name(&self.authentication, Resource::AssetGroup, reference)
+    }
+
+    pub fn list(&self, pagination: Pagination) -> Result<AssetGroups, CommandError> {
+        Ok(AssetGroups::new(pagination::get_all(
+            &self.authentication,
+            "v4/asset-groups",
+            pagination,
+        )?))
+    }
+
+    pub fn get(&self, id: &str) -> Result<AssetGroups, CommandError> {
+        Ok(AssetGroups::new(commands::get(
+            &self.authentication,
+            &format!("v4/asset-groups?id=eq.{id}"),
+        )?))
+    }
+
+    pub fn create(&self, title: &str) -> Result<AssetGroups, CommandError> {
+        validate_title(title)?;
+        let response = commands::post(
+            &self.authentication,
+            "v4/asset-groups",
+            &json!({"title": title}),
+        )?;
+        Ok(AssetGroups::new(response))
+    }
+
+    pub fn update(&self, id: &str, title: &str) -> Result<AssetGroups, CommandError> {
+        validate_title(title)?;
+        let response = commands::patch(
+            &self.authentication,
+            &format!("v4/asset-groups?id=eq.{id}"),
+            &json!({"title": title}),
+        )?;
+        if response.as_array().is_some_and(|groups| groups.is_empty()) {
+            return Err(CommandError::ResourceNotFound(
+                "asset group".to_string(),
+                id.to_string(),
+            ));
+        }
+        Ok(AssetGroups::new(response))
+    }
+
+    /// Deletes the group together with the assets in it.
+    pub fn delete(&self, id: &str) -> Result<(), CommandError> {
+        commands::delete(&self.authentication, &format!("v4/asset-groups?id=eq.{id}"))
+    }
+}
+
+fn validate_title(title: &str) -> Result<(), CommandError> {
+    if title.trim().is_empty() {
+        return Err(CommandError::InvalidArgument(
+            "asset group title cannot be empty".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use httpmock::Method::{DELETE, GET, PATCH, POST};
+    use httpmock::MockServer;
+    use serde_json::json;
+
+    use super::*;
+    use crate::authentication::Config;
+    use crate::commands::{FormatOptions, Formatter, OutputType};
+
+    fn asset_group_command(mock_server: &MockServer) -> AssetGroupCommand {
+        let config = Config::new(mock_server.base_url());
+        AssetGroupCommand::new(Authentication::new_with_config(config, "token"))
+    }
+
+    #[test]
+    fn test_asset_group_crud_should_use_asset_groups_endpoint() {
+        let mock_server = MockServer::start();
+        let group = json!([{"id": "01H2QZ6Z8WXWNDC0KQ198XCZEG", "title": "Menus"}]);
+        let list_mock = mock_server.mock(|when, then| {
+            when.method(GET).path("/v4/asset-groups");
+            then.status(200).json_body(group.clone());
+        });
+        let post_mock = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/v4/asset-groups")
+                .header("Authorization", "Token token")
+                .json_body(json!({"title": "Menus"}));
+            then.status(201).json_body(group.clone());
+        });
+        let patch_mock = mock_server.mock(|when, then| {
+            when.method(PATCH)
+                .path("/v4/asset-groups")
+                .query_param("id", "eq.01H2QZ6Z8WXWNDC0KQ198XCZEG")
+                .json_body(json!({"title": "Lunch menus"}));
+            then.status(200).json_body(json!([]));
+        });
+        let delete_mock = mock_server.mock(|when, then| {
+            when.method(DELETE)
+                .path("/v4/asset-groups")
+                .query_param("id", "eq.01H2QZ6Z8WXWNDC0KQ198XCZEG");
+            then.status(204);
+        });
+        let command = asset_group_command(&mock_server);
+
+        assert_eq!(command.list(Pagination::default()).unwrap().value, group);
+        assert_eq!(command.create("Menus").unwrap().value, group);
+        let error = command
+            .update("01H2QZ6Z8WXWNDC0KQ198XCZEG", "Lunch menus")
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "No asset group matches \"01H2QZ6Z8WXWNDC0KQ198XCZEG\"."
+        );
+        command.delete("01H2QZ6Z8WXWNDC0KQ198XCZEG").unwrap();
+
+        list_mock.assert();
+        post_mock.assert();
+        patch_mock.assert();
+        delete_mock.assert();
+        assert!(matches!(
+            command.create(""),
+            Err(CommandError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_format_asset_groups_should_print_id_and_title() {
+        let groups =
+            AssetGroups::new(json!([{"id": "01H2QZ6Z8WXWNDC0KQ198XCZEG", "title": "Menus"}]));
+
+        let expected_output = "+----------------------------+-------+\n\
+| Id                         | Title |\n\
++----------------------------+-------+\n\
+| 01H2QZ6Z8WXWNDC0KQ198XCZEG | Menus |\n\
++----------------------------+-------+\n";
+        assert_eq!(
+            groups
+                .format_with(&FormatOptions::new(OutputType::HumanReadable))
+                .unwrap(),
+            expected_output
+        );
+    }
+}
@@ -0,0 +1,559 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use log::{debug, warn};
+use reqwest::blocking::{RequestBuilder, Response};
+use reqwest::header::HeaderMap;
+use reqwest::ResponseBuilderExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::commands::CommandError;
+
+const REDACTED: &str = "[REDACTED]";
+const SENSITIVE_HEADERS: [&str; 4] = [
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+];
+const SENSITIVE_FIELDS: [&str; 4] = ["token", "secret", "password", "api_key"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CassetteMode {
+    Record,
+    Replay,
+}
+
+/// Records HTTP exchanges to a directory or replays them from it without touching the network.
+///
+/// Every exchange is one JSON file named after the method, the path and a hash of the request
+/// body, followed by the occurrence number, e.g. `GET-v4_screens-1a2b3c4d-000.json`. The host is
+/// not part of the name, so a recording can be replayed against any `API_BASE_URL`.
+///
+/// A cassette covers one run of the CLI: recording replaces the recordings the directory held,
+/// and every replay starts from the first recording of each request.
+#[derive(Debug)]
+pub struct Cassette {
+    pub mode: CassetteMode,
+    pub dir: PathBuf,
+    // Behind a lock, as parallel uploads number and replay recordings concurrently.
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    // Whether the recordings of an earlier run were cleared.
+    cleared: bool,
+    // The next occurrence to replay of every request.
+    cursor: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    path: String,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<RecordedBody>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<RecordedBody>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum RecordedBody {
+    Json(serde_json::Value),
+    Text(String),
+}
+
+impl RecordedBody {
+    fn new(bytes: &[u8], secret: &str) -> Option<Self> {
+        if bytes.is_empty() {
+            return None;
+        }
+        let text = scrub_text(&String::from_utf8_lossy(bytes), secret);
+        Some(match serde_json::from_str(&text) {
+            Ok(mut value) => {
+                scrub_value(&mut value);
+                RecordedBody::Json(value)
+            }
+            Err(_) => RecordedBody::Text(text),
+        })
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        match self {
+            RecordedBody::Json(value) => value.to_string().into_bytes(),
+            RecordedBody::Text(text) => text.into_bytes(),
+        }
+    }
+
+    fn fingerprint(&self) -> String {
+        match self {
+            RecordedBody::Json(value) => value.to_string(),
+            RecordedBody::Text(text) => text.clone(),
+        }
+    }
+}
+
+impl Cassette {
+    pub fn new(mode: CassetteMode, dir: PathBuf) -> Self {
+        Self {
+            mode,
+            dir,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// Reads `SCREENLY_RECORD` and `SCREENLY_REPLAY`. Replaying wins when both are set.
+    pub fn from_env() -> Option<Self> {
+        let record = env::var("SCREENLY_RECORD").ok().filter(|v| !v.is_empty());
+        let replay = env::var("SCREENLY_REPLAY").ok().filter(|v| !v.is_empty());
+        match (record, replay) {
+            (Some(_), Some(dir)) => {
+                warn!("Both SCREENLY_RECORD and SCREENLY_REPLAY are set. Replaying from {dir}.");
+                Some(Self::new(CassetteMode::Replay, dir.into()))
+            }
+            (None, Some(dir)) => Some(Self::new(CassetteMode::Replay, dir.into())),
+            (Some(dir), None) => Some(Self::new(CassetteMode::Record, dir.into())),
+            (None, None) => None,
+        }
+    }
+
+    /// Sends the request and records the exchange, or answers it from the recording.
+    /// The inner result carries transport errors so that the caller can retry them.
+    /// `secret` is replaced with `[REDACTED]` wherever it appears in a recording.
+    pub fn send(
+        &self,
+        builder: RequestBuilder,
+        secret: &str,
+    ) -> Result<Result<Response, reqwest::Error>, CommandError> {
+        let (client, request) = builder.build_split();
+        let request = request?;
+        let url = request.url().clone();
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        let body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .and_then(|bytes| RecordedBody::new(bytes, secret));
+        let stem = file_stem(request.method().as_str(), &path, body.as_ref());
+
+        if self.mode == CassetteMode::Replay {
+            let interaction = self.next_recording(&stem, request.method().as_str(), &path)?;
+            return Ok(Ok(interaction.response.into_response(url)?));
+        }
+
+        let recorded_request = RecordedRequest {
+            method: request.method().to_string(),
+            path,
+            headers: scrub_headers(request.headers(), secret),
+            body,
+        };
+        let response = match client.execute(request) {
+            Ok(response) => response,
+            Err(e) => return Ok(Err(e)),
+        };
+        let status = response.status().as_u16();
+        let headers = response.headers().clone();
+        let bytes = match response.bytes() {
+            Ok(bytes) => bytes,
+            Err(e) => return Ok(Err(e)),
+        };
+
+        let interaction = Interaction {
+            request: recorded_request,
+            response: RecordedResponse {
+                status,
+                headers: scrub_headers(&headers, secret),
+                body: RecordedBody::new(&bytes, secret),
+            },
+        };
+        self.save(&stem, &interaction)?;
+
+        // Hand back the real response rather than the scrubbed one.
+        let mut builder = http::Response::builder().status(status).url(url);
+        for (name, value) in headers.iter() {
+            builder = builder.header(name, value);
+        }
+        let response = builder
+            .body(bytes.to_vec())
+            .map_err(|e| CommandError::Cassette(e.to_string()))?;
+        Ok(Ok(response.into()))
+    }
+
+    fn save(&self, stem: &str, interaction: &Interaction) -> Result<(), CommandError> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        fs::create_dir_all(&self.dir)?;
+        if !state.cleared {
+            let (recordings, others): (Vec<PathBuf>, Vec<PathBuf>) = fs::read_dir(&self.dir)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .partition(|path| is_recording(path));
+            // Guards against pointing SCREENLY_RECORD at a project directory.
+            if recordings.is_empty() && !others.is_empty() {
+                return Err(CommandError::Cassette(format!(
+                    "{} is not empty and holds no recordings, record into an empty directory",
+                    self.dir.display()
+                )));
+            }
+            for path in recordings {
+                fs::remove_file(path)?;
+            }
+            state.cleared = true;
+        }
+        let occurrence = (0..)
+            .find(|n| !recording_path(&self.dir, stem, *n).exists())
+            .unwrap_or_default();
+        let path = recording_path(&self.dir, stem, occurrence);
+        debug!(
+            "Recording {} {} to {}",
+            interaction.request.method,
+            interaction.request.path,
+            path.display()
+        );
+        fs::write(path, serde_json::to_string_pretty(interaction)?)?;
+        Ok(())
+    }
+
+    /// Picks the next unplayed recording of a request, so that repeated requests play back in
+    /// the order they were recorded. Once a request runs out of recordings its last one is
+    /// played again.
+    fn next_recording(
+        &self,
+        stem: &str,
+        method: &str,
+        path: &str,
+    ) -> Result<Interaction, CommandError> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let position = state.cursor.get(stem).copied().unwrap_or_default();
+        let occurrence = (0..=position)
+            .rev()
+            .find(|n| recording_path(&self.dir, stem, *n).exists())
+            .ok_or_else(|| {
+                CommandError::Cassette(format!(
+                    "no recording of {method} {path} in {}",
+                    self.dir.display()
+                ))
+            })?;
+        let recording = recording_path(&self.dir, stem, occurrence);
+        debug!("Replaying {method} {path} from {}", recording.display());
+        let interaction = serde_json::from_str(&fs::read_to_string(recording)?)?;
+
+        state
+            .cursor
+            .insert(stem.to_string(), position.max(occurrence) + 1);
+        Ok(interaction)
+    }
+}
+
+impl RecordedResponse {
+    fn into_response(self, url: reqwest::Url) -> Result<Response, CommandError> {
+        let mut builder = http::Response::builder().status(self.status).url(url);
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+        let body = self.body.map(RecordedBody::into_bytes).unwrap_or_default();
+        let response = builder
+            .body(body)
+            .map_err(|e| CommandError::Cassette(e.to_string()))?;
+        Ok(response.into())
+    }
+}
+
+fn recording_path(dir: &Path, stem: &str, occurrence: usize) -> PathBuf {
+    dir.join(format!("{stem}-{occurrence:03}.json"))
+}
+
+// Matches the names given by `recording_path`, e.g. `GET-v4_screens-1a2b3c4d-000.json`.
+fn is_recording(path: &Path) -> bool {
+    let Some(name) = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_suffix(".json"))
+    else {
+        return false;
+    };
+    let mut parts = name.rsplitn(3, '-');
+    let (Some(occurrence), Some(hash), Some(request)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return false;
+    };
+    let Some((method, slug)) = request.split_once('-') else {
+        return false;
+    };
+    occurrence.len() >= 3
+        && occurrence.chars().all(|c| c.is_ascii_digit())
+        && hash.len() == 8
+        && hash.chars().all(|c| c.is_ascii_hexdigit())
+        && !method.is_empty()
+        && method.chars().all(|c| c.is_ascii_uppercase())
+        && slug.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && path.is_file()
+}
+
+fn file_stem(method: &str, path: &str, body: Option<&RecordedBody>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method);
+    hasher.update(path);
+    if let Some(body) = body {
+        hasher.update(body.fingerprint());
+    }
+    let digest = hasher.finalize();
+    let hash: String = digest[..4].iter().map(|b| format!("{b:02x}")).collect();
+
+    let slug: String = path
+        .trim_start_matches('/')
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .take(60)
+        .collect();
+    format!("{method}-{slug}-{hash}")
+}
+
+fn scrub_headers(headers: &HeaderMap, secret: &str) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .filter(|(name, _)| !SENSITIVE_HEADERS.contains(&name.as_str()))
+        .filter_map(|(name, value)| {
+            let value = value.to_str().ok()?;
+            Some((name.to_string(), scrub_text(value, secret)))
+        })
+        .collect()
+}
+
+fn scrub_text(text: &str, secret: &str) -> String {
+    if secret.is_empty() {
+        return text.to_string();
+    }
+    text.replace(secret, REDACTED)
+}
+
+fn scrub_value(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(fields) => {
+            for (key, field) in fields.iter_mut() {
+                let key = key.to_lowercase();
+                if field.is_string() && SENSITIVE_FIELDS.iter().any(|name| key.contains(name)) {
+                    *field = serde_json::Value::String(REDACTED.to_string());
+                } else {
+                    scrub_value(field);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(scrub_value),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use httpmock::Method::{GET, POST};
+    use httpmock::MockServer;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn test_record_should_scrub_token_and_replay_without_network() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method(GET).path("/v4/screens");
+            then.status(200)
+                .header("Content-Range", "0-0/1")
+                .json_body(json!([{"id": "screen-1", "api_key": "abc"}]));
+        });
+        let dir = tempdir().unwrap();
+        let client = reqwest::blocking::Client::new();
+        let url = format!("{}/v4/screens", mock_server.base_url());
+
+        let recorder = Cassette::new(CassetteMode::Record, dir.path().to_path_buf());
+        let builder = client
+            .get(&url)
+            .header("Authorization", "Token secret-token");
+        let response = recorder.send(builder, "secret-token").unwrap().unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.text().unwrap(),
+            r#"[{"api_key":"abc","id":"screen-1"}]"#
+        );
+        mock.assert();
+
+        let files: Vec<_> = fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(files.len(), 1);
+        let recording = fs::read_to_string(files[0].as_ref().unwrap().path()).unwrap();
+        assert!(!recording.contains("secret-token"));
+        assert!(!recording.contains("abc"));
+
+        mock_server.reset();
+        let player = Cassette::new(CassetteMode::Replay, dir.path().to_path_buf());
+        let response = player.send(client.get(&url), "").unwrap().unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.url().as_str(), url);
+        assert_eq!(response.headers()["Content-Range"], "0-0/1");
+        assert_eq!(
+            response.json::<serde_json::Value>().unwrap(),
+            json!([{"id": "screen-1", "api_key": REDACTED}])
+        );
+    }
+
+    #[test]
+    fn test_replay_should_play_repeated_requests_in_order() {
+        let mock_server = MockServer::start();
+        let dir = tempdir().unwrap();
+        let client = reqwest::blocking::Client::new();
+        let url = format!("{}/v4/screens", mock_server.base_url());
+        let recorder = Cassette::new(CassetteMode::Record, dir.path().to_path_buf());
+
+        for screens in [json!([]), json!([{"id": "screen-1"}])] {
+            let mut mock = mock_server.mock(|when, then| {
+                when.method(GET).path("/v4/screens");
+                then.status(200).json_body(screens);
+            });
+            recorder.send(client.get(&url), "token").unwrap().unwrap();
+            mock.delete();
+        }
+
+        let player = Cassette::new(CassetteMode::Replay, dir.path().to_path_buf());
+        let replay = || {
+            player
+                .send(client.get(&url), "")
+                .unwrap()
+                .unwrap()
+                .json::<serde_json::Value>()
+                .unwrap()
+        };
+        assert_eq!(replay(), json!([]));
+        assert_eq!(replay(), json!([{"id": "screen-1"}]));
+        // Out of recordings, the last one keeps being played.
+        assert_eq!(replay(), json!([{"id": "screen-1"}]));
+
+        // Another run replays the cassette from the start and leaves it untouched.
+        let files = fs::read_dir(dir.path()).unwrap().count();
+        let player = Cassette::new(CassetteMode::Replay, dir.path().to_path_buf());
+        let response = player.send(client.get(&url), "").unwrap().unwrap();
+        assert_eq!(response.json::<serde_json::Value>().unwrap(), json!([]));
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), files);
+    }
+
+    #[test]
+    fn test_record_should_replace_earlier_recordings() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method(GET).path("/v4/screens");
+            then.status(200).json_body(json!([]));
+        });
+        let dir = tempdir().unwrap();
+        let client = reqwest::blocking::Client::new();
+        let url = format!("{}/v4/screens", mock_server.base_url());
+
+        for _ in 0..2 {
+            let recorder = Cassette::new(CassetteMode::Record, dir.path().to_path_buf());
+            recorder.send(client.get(&url), "token").unwrap().unwrap();
+        }
+
+        let files: Vec<String> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(files.len(), 1);
+        assert!(files[0].ends_with("-000.json"));
+    }
+
+    #[test]
+    fn test_record_should_keep_files_that_are_not_recordings() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method(GET).path("/v4/screens");
+            then.status(200).json_body(json!([]));
+        });
+        let dir = tempdir().unwrap();
+        let package = dir.path().join("package.json");
+        fs::write(&package, "{}").unwrap();
+        let client = reqwest::blocking::Client::new();
+        let url = format!("{}/v4/screens", mock_server.base_url());
+
+        let recorder = Cassette::new(CassetteMode::Record, dir.path().to_path_buf());
+        assert!(matches!(
+            recorder.send(client.get(&url), "token"),
+            Err(CommandError::Cassette(_))
+        ));
+        assert!(package.exists());
+
+        let stale = recording_path(dir.path(), "GET-v4_labels-1a2b3c4d", 0);
+        fs::write(&stale, "{}").unwrap();
+        let recorder = Cassette::new(CassetteMode::Record, dir.path().to_path_buf());
+        recorder.send(client.get(&url), "token").unwrap().unwrap();
+        assert!(package.exists());
+        assert!(!stale.exists());
+    }
+
+    #[test]
+    fn test_replay_should_tell_requests_apart_by_body() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/v4/labels")
+                .json_body(json!({"name": "a"}));
+            then.status(201).json_body(json!([{"name": "a"}]));
+        });
+        mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/v4/labels")
+                .json_body(json!({"name": "b"}));
+            then.status(201).json_body(json!([{"name": "b"}]));
+        });
+        let dir = tempdir().unwrap();
+        let client = reqwest::blocking::Client::new();
+        let url = format!("{}/v4/labels", mock_server.base_url());
+
+        let recorder = Cassette::new(CassetteMode::Record, dir.path().to_path_buf());
+        for name in ["a", "b"] {
+            let builder = client.post(&url).json(&json!({ "name": name }));
+            recorder.send(builder, "token").unwrap().unwrap();
+        }
+
+        let player = Cassette::new(CassetteMode::Replay, dir.path().to_path_buf());
+        let builder = client.post(&url).json(&json!({"name": "b"}));
+        let response = player.send(builder, "").unwrap().unwrap();
+        assert_eq!(
+            response.json::<serde_json::Value>().unwrap(),
+            json!([{"name": "b"}])
+        );
+    }
+
+    #[test]
+    fn test_replay_without_recording_should_fail() {
+        let dir = tempdir().unwrap();
+        let client = reqwest::blocking::Client::new();
+        let player = Cassette::new(CassetteMode::Replay, dir.path().to_path_buf());
+
+        let result = player.send(client.delete("http://localhost/v4/screens?id=eq.1"), "");
+        assert!(matches!(result, Err(CommandError::Cassette(_))));
+    }
+}
@@ -0,0 +1,403 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use clap::{Command, ValueEnum};
+use clap_complete::Shell;
+
+use crate::commands::CommandError;
+
+/// Resources whose ids are completed from the local cache.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum IdKind {
+    Screen,
+    Asset,
+    Playlist,
+}
+
+impl IdKind {
+    fn name(self) -> &'static str {
+        match self {
+            IdKind::Screen => "screen",
+            IdKind::Asset => "asset",
+            IdKind::Playlist => "playlist",
+        }
+    }
+
+    fn for_command(name: &str) -> Option<Self> {
+        match name {
+            "screen" => Some(IdKind::Screen),
+            "asset" => Some(IdKind::Asset),
+            "playlist" => Some(IdKind::Playlist),
+            _ => None,
+        }
+    }
+}
+
+// Ids are cached per profile, different accounts see different resources.
+fn cache_path(profile: &str, kind: IdKind) -> Option<PathBuf> {
+    let dir = dirs::cache_dir()?.join("screenly").join(profile);
+    Some(dir.join(format!("{}-ids.txt", kind.name())))
+}
+
+/// Remembers the ids and names of listed resources, one `id<TAB>name` line each.
+pub fn cache_ids(
+    profile: &str,
+    kind: IdKind,
+    rows: &serde_json::Value,
+) -> Result<(), CommandError> {
+    let Some(path) = cache_path(profile, kind) else {
+        return Ok(());
+    };
+    let lines: Vec<String> = rows
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|row| {
+            let id = row["id"].as_str()?;
+            let name = row["name"]
+                .as_str()
+                .or(row["title"].as_str())
+                .unwrap_or_default();
+            let name: String = name
+                .chars()
+                .map(|c| if c.is_control() { ' ' } else { c })
+                .collect();
+            Some(format!("{id}\t{name}"))
+        })
+        .collect();
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, lines.join("\n") + "\n")?;
+    Ok(())
+}
+
+pub fn cached_ids(profile: &str, kind: IdKind) -> Vec<String> {
+    cache_path(profile, kind)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|contents| contents.lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Writes the completion script of `cmd` for `shell`. Bash, zsh and fish scripts also
+/// complete screen, asset and playlist ids from the cache filled by the list commands.
+pub fn generate(shell: Shell, cmd: &mut Command, out: &mut dyn Write) -> io::Result<()> {
+    let name = cmd.get_name().to_string();
+    clap_complete::generate(shell, cmd, &name, out);
+
+    let arguments = id_arguments(cmd);
+    let options = value_options(cmd);
+    let hook = match shell {
+        Shell::Bash => bash_hook(&name, &arguments, &options),
+        Shell::Zsh => zsh_hook(&name, &arguments, &options),
+        Shell::Fish => fish_hook(&name, &arguments, &options),
+        _ => return Ok(()),
+    };
+    out.write_all(hook.as_bytes())
+}
+
+/// Positional arguments holding ids, as `(command, subcommand, position, kind)`.
+/// Positions start at 1.
+fn id_arguments(cmd: &Command) -> Vec<(String, String, usize, IdKind)> {
+    let mut arguments = Vec::new();
+    for command in cmd.get_subcommands() {
+        let Some(command_kind) = IdKind::for_command(command.get_name()) else {
+            continue;
+        };
+        for subcommand in command.get_subcommands() {
+            for (index, arg) in subcommand.get_positionals().enumerate() {
+                let kind = match arg.get_id().as_str() {
+                    "uuid" => command_kind,
+                    "asset_uuid" => IdKind::Asset,
+                    _ => continue,
+                };
+                arguments.push((
+                    command.get_name().to_string(),
+                    subcommand.get_name().to_string(),
+                    index + 1,
+                    kind,
+                ));
+            }
+        }
+    }
+    arguments
+}
+
+/// Options of the top-level command that take a value, such as `--profile`. The hooks skip
+/// them, and their value, to find the command when they come first.
+fn value_options(cmd: &Command) -> Vec<String> {
+    cmd.get_arguments()
+        .filter(|arg| !arg.is_positional() && arg.get_action().takes_values())
+        .flat_map(|arg| {
+            let long = arg.get_long().map(|long| format!("--{long}"));
+            let short = arg.get_short().map(|short| format!("-{short}"));
+            long.into_iter().chain(short)
+        })
+        .collect()
+}
+
+// Groups the `"<command> <subcommand> <word index>"` patterns by the kind of id they take.
+// `offset` is the word index of the first positional argument of a subcommand.
+fn patterns(
+    arguments: &[(String, String, usize, IdKind)],
+    offset: usize,
+) -> Vec<(IdKind, Vec<String>)> {
+    let mut grouped: Vec<(IdKind, Vec<String>)> = Vec::new();
+    for (command, subcommand, position, kind) in arguments {
+        let pattern = format!("\"{command} {subcommand} {}\"", offset + position - 1);
+        match grouped.iter_mut().find(|(k, _)| k == kind) {
+            Some((_, patterns)) => patterns.push(pattern),
+            None => grouped.push((*kind, vec![pattern])),
+        }
+    }
+    grouped
+}
+
+fn bash_hook(
+    name: &str,
+    arguments: &[(String, String, usize, IdKind)],
+    options: &[String],
+) -> String {
+    let cases: String = patterns(arguments, 3)
+        .iter()
+        .map(|(kind, patterns)| {
+            format!("        {}) kind={} ;;\n", patterns.join("|"), kind.name())
+        })
+        .collect();
+    // Bash splits `--profile=acme` into three words.
+    let skip_values = match options {
+        [] => String::new(),
+        options => format!(
+            "            {}) [[ \"${{COMP_WORDS[i+1]}}\" == \"=\" ]] && ((i += 3)) || ((i += 2)) ;;\n",
+            options.join("|")
+        ),
+    };
+    format!(
+        r#"
+_{name}_ids() {{
+    local kind=""
+    local i=1
+    while [[ $i -lt $COMP_CWORD && "${{COMP_WORDS[i]}}" == -* ]]; do
+        case "${{COMP_WORDS[i]}}" in
+{skip_values}            *) ((i += 1)) ;;
+        esac
+    done
+    case "${{COMP_WORDS[i]}} ${{COMP_WORDS[i+1]}} $((COMP_CWORD - i + 1))" in
+{cases}    esac
+    if [[ -n "$kind" && "${{COMP_WORDS[COMP_CWORD]}}" != -* ]]; then
+        local ids
+        ids="$({name} complete-ids "$kind" 2>/dev/null | cut -f1)"
+        COMPREPLY=($(compgen -W "$ids" -- "${{COMP_WORDS[COMP_CWORD]}}"))
+        return 0
+    fi
+    _{name} "$@"
+}}
+
+if [[ "${{BASH_VERSINFO[0]}}" -eq 4 && "${{BASH_VERSINFO[1]}}" -ge 4 || "${{BASH_VERSINFO[0]}}" -gt 4 ]]; then
+    complete -F _{name}_ids -o nosort -o bashdefault -o default {name}
+else
+    complete -F _{name}_ids -o bashdefault -o default {name}
+fi
+"#
+    )
+}
+
+fn zsh_hook(
+    name: &str,
+    arguments: &[(String, String, usize, IdKind)],
+    options: &[String],
+) -> String {
+    let cases: String = patterns(arguments, 4)
+        .iter()
+        .map(|(kind, patterns)| {
+            format!("        {}) kind={} ;;\n", patterns.join("|"), kind.name())
+        })
+        .collect();
+    let skip_values = match options {
+        [] => String::new(),
+        options => format!("            {}) (( i += 2 )) ;;\n", options.join("|")),
+    };
+    format!(
+        r#"
+_{name}_ids() {{
+    local kind
+    local i=2
+    while (( i < CURRENT )) && [[ $words[i] == -* ]]; do
+        case $words[i] in
+{skip_values}            *) (( i += 1 )) ;;
+        esac
+    done
+    case "${{words[i]}} ${{words[i+1]}} $(( CURRENT - i + 2 ))" in
+{cases}    esac
+    if [[ -n $kind && $PREFIX != -* ]]; then
+        local -a ids
+        ids=(${{(f)"$({name} complete-ids $kind 2>/dev/null)"}})
+        ids=("${{(@)ids//$'\t'/:}}")
+        _describe -t ids "$kind id" ids && return 0
+    fi
+    _{name} "$@"
+}}
+
+compdef _{name}_ids {name}
+"#
+    )
+}
+
+fn fish_hook(
+    name: &str,
+    arguments: &[(String, String, usize, IdKind)],
+    options: &[String],
+) -> String {
+    let cases: String = patterns(arguments, 3)
+        .iter()
+        .map(|(kind, patterns)| {
+            format!(
+                "        case {}\n            echo {}\n",
+                patterns.join(" "),
+                kind.name()
+            )
+        })
+        .collect();
+    let skip_values = match options {
+        [] => String::new(),
+        options => format!(
+            "            case {}\n                set i (math $i + 2)\n",
+            options.join(" ")
+        ),
+    };
+    format!(
+        r#"
+function __{name}_id_kind
+    set -l tokens (commandline -opc)
+    set -l i 2
+    while test $i -le (count $tokens); and string match -q -- '-*' $tokens[$i]
+        switch $tokens[$i]
+{skip_values}            case '*'
+                set i (math $i + 1)
+        end
+    end
+    switch "$tokens[$i] $tokens[(math $i + 1)] "(math (count $tokens) - $i + 2)
+{cases}        case '*'
+            return 1
+    end
+end
+
+complete -c {name} -n __{name}_id_kind -f -a "({name} complete-ids (__{name}_id_kind))"
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+
+    use clap::{Parser, Subcommand};
+    use envtestkit::lock::lock_test;
+    use envtestkit::set_env;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[derive(Parser)]
+    #[command(name = "screenly")]
+    struct TestCli {
+        #[arg(long, global = true)]
+        profile: Option<String>,
+        #[arg(long, global = true)]
+        dry_run: bool,
+        #[command(subcommand)]
+        command: TestCommands,
+    }
+
+    #[derive(Subcommand)]
+    enum TestCommands {
+        #[command(subcommand)]
+        Playlist(TestPlaylistCommands),
+    }
+
+    #[derive(Subcommand)]
+    enum TestPlaylistCommands {
+        Get { uuid: String },
+        Append { uuid: String, asset_uuid: String },
+    }
+
+    #[test]
+    fn test_cache_ids_should_store_ids_per_profile() {
+        let tmp_dir = tempdir().unwrap();
+        let _lock = lock_test();
+        let _cache = set_env(
+            OsString::from("XDG_CACHE_HOME"),
+            tmp_dir.path().to_str().unwrap(),
+        );
+        let screens = json!([
+            {"id": "01H2QZ6Z8WXWNDC0KQ198XCZEW", "name": "Lobby\tscreen"},
+            {"id": "01H2QZ6Z8WXWNDC0KQ198XCZEB", "name": null},
+        ]);
+
+        cache_ids("acme", IdKind::Screen, &screens).unwrap();
+
+        assert_eq!(
+            cached_ids("acme", IdKind::Screen),
+            vec![
+                "01H2QZ6Z8WXWNDC0KQ198XCZEW\tLobby screen",
+                "01H2QZ6Z8WXWNDC0KQ198XCZEB\t"
+            ]
+        );
+        assert!(cached_ids("default", IdKind::Screen).is_empty());
+        assert!(cached_ids("acme", IdKind::Asset).is_empty());
+    }
+
+    #[test]
+    fn test_id_arguments_should_find_uuid_positionals() {
+        let cmd = <TestCli as clap::CommandFactory>::command();
+
+        assert_eq!(
+            id_arguments(&cmd),
+            vec![
+                ("playlist".into(), "get".into(), 1, IdKind::Playlist),
+                ("playlist".into(), "append".into(), 1, IdKind::Playlist),
+                ("playlist".into(), "append".into(), 2, IdKind::Asset),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generate_bash_should_hook_id_completion() {
+        let mut cmd = <TestCli as clap::CommandFactory>::command();
+        let mut script = Vec::new();
+        generate(Shell::Bash, &mut cmd, &mut script).unwrap();
+        let script = String::from_utf8(script).unwrap();
+
+        assert!(script.contains("_screenly() {"));
+        assert!(
+            script.contains(r#"        "playlist get 3"|"playlist append 3") kind=playlist ;;"#)
+        );
+        assert!(script.contains(r#"        "playlist append 4") kind=asset ;;"#));
+        assert!(script.contains("complete -F _screenly_ids"));
+    }
+
+    #[test]
+    fn test_generate_should_skip_leading_options_with_values() {
+        let cmd = <TestCli as clap::CommandFactory>::command();
+        assert_eq!(value_options(&cmd), vec!["--profile"]);
+
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            let mut cmd = <TestCli as clap::CommandFactory>::command();
+            let mut script = Vec::new();
+            generate(shell, &mut cmd, &mut script).unwrap();
+            let script = String::from_utf8(script).unwrap();
+
+            let skip = match shell {
+                Shell::Fish => "case --profile\n",
+                _ => "--profile)",
+            };
+            assert!(
+                script.contains(skip),
+                "{shell} hook does not skip --profile"
+            );
+        }
+    }
+}
@@ -10,7 +10,7 @@ use indicatif::ProgressBar;
 use log::debug;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use reqwest::header::HeaderMap;
-use reqwest::StatusCode;
+use reqwest::{Method, StatusCode};
 use serde_json::json;
 use serde_yaml;
 
@@ -27,7 +27,8 @@ use crate::commands::edge_app::utils::{
     SettingChanges,
 };
 use crate::commands::edge_app::EdgeAppCommand;
-use crate::commands::{CommandError, EdgeApps};
+use crate::commands::retry::send_with_retry;
+use crate::commands::{dry_run, ApiError, CommandError, EdgeApps};
 
 // Edge apps commands
 impl EdgeAppCommand {
@@ -186,13 +187,20 @@ impl EdgeAppCommand {
         }
 
         // now that we know we have changes, we can create a new version
+        let dry_run = self.api.authentication.config.dry_run;
         let revision =
-            self.create_version(&manifest, generate_file_tree(&local_files, edge_app_dir))?;
+            match self.create_version(&manifest, generate_file_tree(&local_files, edge_app_dir))? {
+                Some(revision) => revision,
+                // A dry run creates nothing. Revisions are sequential, so plan with the next one.
+                None => revision + 1,
+            };
 
         self.upload_changed_files(edge_app_dir, &actual_app_id, revision, &changed_files)?;
         debug!("Files uploaded");
 
-        self.ensure_assets_processing_finished(&actual_app_id, revision)?;
+        if !dry_run {
+            self.ensure_assets_processing_finished(&actual_app_id, revision)?;
+        }
         // now we freeze it by publishing it
         self.api.publish_version(&actual_app_id, revision)?;
         debug!("Edge App published.");
@@ -208,7 +216,8 @@ impl EdgeAppCommand {
         revision: u32,
         channel: &str,
     ) -> Result<(), CommandError> {
-        let version_exists = self.api.version_exists(app_id, revision)?;
+        let version_exists =
+            self.api.authentication.config.dry_run || self.api.version_exists(app_id, revision)?;
         if !version_exists {
             return Err(CommandError::RevisionNotFound(revision.to_string()));
         }
@@ -376,7 +385,7 @@ impl EdgeAppCommand {
         &self,
         manifest: &EdgeAppManifest,
         file_tree: HashMap<String, String>,
-    ) -> Result<u32, CommandError> {
+    ) -> Result<Option<u32>, CommandError> {
         let mut json = EdgeAppManifest::prepare_payload(manifest);
         json.insert("file_tree", json!(file_tree));
 
@@ -531,34 +540,44 @@ impl EdgeAppCommand {
         headers.insert("Prefer", "return=representation".parse()?);
 
         debug!("Uploading file: {path:?}");
-        let form = reqwest::blocking::multipart::Form::new()
-            .text(
-                "title",
-                path.file_name()
-                    .ok_or(CommandError::FileSystemError(
-                        "Can't obtain file name".to_owned(),
-                    ))?
-                    .to_string_lossy()
-                    .to_string(),
-            )
-            .text("app_id", app_id.to_string())
-            .text("app_revision", revision.to_string())
-            .file("file", path)?;
-
-        let response = self
-            .api
-            .authentication
-            .build_client()?
-            .post(url)
-            .multipart(form)
-            .headers(headers)
-            .timeout(Duration::from_secs(3600)) // timeout is equal to server timeout
-            .send()?;
+        let title = path
+            .file_name()
+            .ok_or(CommandError::FileSystemError(
+                "Can't obtain file name".to_owned(),
+            ))?
+            .to_string_lossy()
+            .to_string();
+
+        let authentication = &self.api.authentication;
+        if authentication.config.dry_run {
+            let payload = json!({
+                "title": title,
+                "app_id": app_id,
+                "app_revision": revision,
+                "file": path,
+            });
+            dry_run(Method::POST, &url, Some(payload))?;
+            return Ok(());
+        }
+
+        let client = authentication.build_client()?;
+        let response = send_with_retry(authentication, false, || {
+            let form = reqwest::blocking::multipart::Form::new()
+                .text("title", title.clone())
+                .text("app_id", app_id.to_string())
+                .text("app_revision", revision.to_string())
+                .file("file", path)?;
+
+            Ok(client
+                .post(&url)
+                .multipart(form)
+                .headers(headers.clone())
+                .timeout(Duration::from_secs(3600))) // timeout is equal to server timeout
+        })?;
 
         let status = response.status();
         if status != StatusCode::CREATED {
-            debug!("Response: {:?}", &response.text());
-            return Err(CommandError::WrongResponseStatus(status.as_u16()));
+            return Err(ApiError::from_response(Method::POST, response).into());
         }
 
         Ok(())
@@ -0,0 +1,183 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::Utc;
+use serde_json::json;
+
+use crate::authentication::Authentication;
+use crate::commands::pagination::{self, Pagination};
+use crate::commands::schedule;
+use crate::commands::{CommandError, ScreenStatuses};
+
+/// Summarises the health of `screens`, a list of `v4/screens` rows.
+///
+/// Besides the online state, last ping, software version and uptime of each screen,
+/// the summary names the playlists the screen should be playing right now, see
+/// [`schedule::playing`].
+pub fn screen_statuses(
+    authentication: &Authentication,
+    screens: &serde_json::Value,
+) -> Result<ScreenStatuses, CommandError> {
+    let all = |endpoint: &str| pagination::get_all(authentication, endpoint, Pagination::default());
+    let screen_links = all("v4/labels/screens?select=label_id,screen_id")?;
+    let playlist_links = all("v4/labels/playlists?select=label_id,playlist_id")?;
+    let screen_labels = group(&screen_links, "screen_id", "label_id");
+    let label_playlists = group(&playlist_links, "label_id", "playlist_id");
+    let playlists = all("v4/playlists?select=id,title,is_enabled,priority,predicate")?;
+    let playlists: BTreeMap<&str, &serde_json::Value> = playlists
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|playlist| Some((playlist["id"].as_str()?, playlist)))
+        .collect();
+    let now = Utc::now();
+
+    let statuses = screens
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|screen| {
+            let id = screen["id"].as_str().unwrap_or_default();
+            let reachable: BTreeSet<&str> = screen_labels
+                .get(id)
+                .into_iter()
+                .flatten()
+                .filter_map(|label| label_playlists.get(label))
+                .flatten()
+                .copied()
+                .collect();
+            let showing = schedule::playing(
+                reachable
+                    .iter()
+                    .filter_map(|playlist| playlists.get(playlist).copied()),
+                schedule::local_time(&screen["timezone"], now),
+            );
+            let titles: Vec<&str> = showing
+                .iter()
+                .filter_map(|playlist| playlist["title"].as_str())
+                .collect();
+
+            let online = screen["status"]
+                .as_str()
+                .is_some_and(|status| status.eq_ignore_ascii_case("online"));
+            json!({
+                "id": screen["id"],
+                "name": screen["name"],
+                "status": if online { "online" } else { "offline" },
+                "last_ping": screen["last_ping"],
+                "software_version": screen["software_version"],
+                "uptime": screen["uptime"],
+                "playlist": if titles.is_empty() { serde_json::Value::Null } else { json!(titles.join(", ")) },
+            })
+        })
+        .collect();
+    Ok(ScreenStatuses::new(serde_json::Value::Array(statuses)))
+}
+
+// Maps every `key` of the join table rows to its `value`s.
+fn group<'a>(
+    rows: &'a serde_json::Value,
+    key: &str,
+    value: &str,
+) -> BTreeMap<&'a str, Vec<&'a str>> {
+    let mut grouped: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for row in rows.as_array().into_iter().flatten() {
+        if let (Some(key), Some(value)) = (row[key].as_str(), row[value].as_str()) {
+            grouped.entry(key).or_default().push(value);
+        }
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use httpmock::Method::GET;
+    use httpmock::MockServer;
+
+    use super::*;
+    use crate::authentication::Config;
+    use crate::commands::{FormatOptions, Formatter, OutputType, RowFilter};
+
+    #[test]
+    fn test_screen_statuses_should_summarise_state_and_playlists() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method(GET).path("/v4/labels/screens");
+            then.status(200).json_body(json!([
+                {"label_id": "lobby", "screen_id": "screen-1"},
+                {"label_id": "north", "screen_id": "screen-1"},
+                {"label_id": "north", "screen_id": "screen-2"},
+            ]));
+        });
+        mock_server.mock(|when, then| {
+            when.method(GET).path("/v4/labels/playlists");
+            then.status(200).json_body(json!([
+                {"label_id": "lobby", "playlist_id": "welcome"},
+                {"label_id": "north", "playlist_id": "menus"},
+                {"label_id": "north", "playlist_id": "alert"},
+                {"label_id": "north", "playlist_id": "retired"},
+                {"label_id": "north", "playlist_id": "never"},
+            ]));
+        });
+        mock_server.mock(|when, then| {
+            when.method(GET).path("/v4/playlists");
+            then.status(200).json_body(json!([
+                {"id": "welcome", "title": "Welcome", "is_enabled": true, "priority": false},
+                {"id": "menus", "title": "Menus", "is_enabled": true, "priority": false},
+                {"id": "alert", "title": "Alert", "is_enabled": true, "priority": true},
+                {"id": "retired", "title": "Retired", "is_enabled": false, "priority": true},
+                {"id": "never", "title": "Never", "is_enabled": true, "priority": true, "predicate": "FALSE"},
+            ]));
+        });
+        let screens = json!([
+            {"id": "screen-1", "name": "Lobby", "status": "Online", "last_ping": "2026-10-17T08:00:00+00:00", "software_version": "2.1", "uptime": 3600},
+            {"id": "screen-2", "name": "Kitchen", "status": "Offline", "last_ping": null, "software_version": "2.0", "uptime": null},
+            {"id": "screen-3", "name": "Spare", "status": "Offline"},
+        ]);
+
+        let config = Config::new(mock_server.base_url());
+        let authentication = Authentication::new_with_config(config, "token");
+        let statuses = screen_statuses(&authentication, &screens).unwrap();
+
+        assert_eq!(statuses.value[0]["status"], "online");
+        assert_eq!(statuses.value[0]["playlist"], "Alert");
+        assert_eq!(statuses.value[0]["software_version"], "2.1");
+        assert_eq!(statuses.value[1]["status"], "offline");
+        assert_eq!(statuses.value[1]["playlist"], "Alert");
+        assert!(statuses.value[2]["playlist"].is_null());
+    }
+
+    #[test]
+    fn test_format_screen_statuses_should_show_state_and_uptime() {
+        let statuses = ScreenStatuses::new(json!([
+            {"id": "screen-1", "name": "Lobby", "status": "online", "last_ping": "2026-10-17T08:00:00+00:00", "software_version": "2.1", "uptime": "7200.5", "playlist": "Alert"},
+            {"id": "screen-2", "name": "Kitchen", "status": "offline", "last_ping": null, "software_version": null, "uptime": null, "playlist": null},
+        ]));
+
+        let output = statuses
+            .format_with(&FormatOptions::new(OutputType::HumanReadable))
+            .unwrap();
+        assert!(output.contains("🟢 online"));
+        assert!(output.contains("🔴 offline"));
+        assert!(output.contains("2 hours"));
+        assert!(output.contains("Alert"));
+    }
+
+    #[test]
+    fn test_offline_should_only_count_filtered_screens() {
+        let statuses = ScreenStatuses::new(json!([
+            {"id": "screen-1", "name": "Lobby", "status": "online"},
+            {"id": "screen-2", "name": "Kitchen", "status": "offline"},
+            {"id": "screen-3", "name": "Kiosk", "status": "offline"},
+        ]));
+
+        assert_eq!(
+            statuses.offline(&FormatOptions::new(OutputType::HumanReadable)),
+            2
+        );
+        let options = FormatOptions {
+            filters: vec!["name~^L".parse::<RowFilter>().unwrap()],
+            ..FormatOptions::new(OutputType::HumanReadable)
+        };
+        assert_eq!(statuses.offline(&options), 0);
+    }
+}
@@ -0,0 +1,452 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::path::Path;
+
+use indicatif::{ProgressBar, ProgressStyle};
+use rayon::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::authentication::Authentication;
+use crate::commands::lookup::{self, Resource};
+use crate::commands::playlist;
+use crate::commands::screen::{self, ScreenUpdate};
+use crate::commands::{created_id, label, CommandError, ImportReport};
+
+/// One screen of an inventory file.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct InventoryRow {
+    pub pin: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Labels to put on the screen. Missing labels are created.
+    #[serde(default, deserialize_with = "deserialize_labels")]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Playlist to show on the screen, and on no other screen of its labels.
+    #[serde(default)]
+    pub playlist: Option<String>,
+}
+
+// CSV cells hold labels separated by `;`, YAML may use a list as well.
+fn deserialize_labels<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Labels {
+        List(Vec<String>),
+        Joined(String),
+    }
+
+    let labels = match Option::<Labels>::deserialize(deserializer)? {
+        Some(Labels::List(labels)) => labels,
+        Some(Labels::Joined(labels)) => labels.split(';').map(str::to_string).collect(),
+        None => Vec::new(),
+    };
+    Ok(labels
+        .into_iter()
+        .map(|label| label.trim().to_string())
+        .filter(|label| !label.is_empty())
+        .collect())
+}
+
+/// Reads a `.csv` inventory with a header row, or a `.yml`/`.yaml` list of rows.
+pub fn read_inventory(path: &Path) -> Result<Vec<InventoryRow>, CommandError> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let rows = match extension.as_str() {
+        "csv" => csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(File::open(path)?)
+            .deserialize()
+            .collect::<Result<Vec<InventoryRow>, _>>()
+            .map_err(|e| CommandError::InvalidArgument(format!("{}: {e}", path.display())))?,
+        "yml" | "yaml" => serde_yaml::from_reader(File::open(path)?)?,
+        _ => {
+            return Err(CommandError::InvalidArgument(format!(
+                "{}: inventory must be a .csv, .yml or .yaml file",
+                path.display()
+            )))
+        }
+    };
+    validate(&rows)?;
+    Ok(rows)
+}
+
+fn validate(rows: &[InventoryRow]) -> Result<(), CommandError> {
+    let mut problems = Vec::new();
+    let mut pins = BTreeMap::new();
+    for (index, row) in rows.iter().enumerate() {
+        let number = index + 1;
+        if row.pin.trim().is_empty() {
+            problems.push(format!("row {number}: pin is missing"));
+        } else if let Some(first) = pins.insert(row.pin.trim(), number) {
+            problems.push(format!(
+                "row {number}: pin {} is already used in row {first}",
+                row.pin
+            ));
+        }
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(CommandError::InvalidArgument(problems.join("; ")))
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RowOutcome {
+    row: usize,
+    pin: String,
+    name: Option<String>,
+    status: &'static str,
+    screen_id: Option<String>,
+    error: Option<String>,
+}
+
+// Ids by label or playlist name, or why the name could not be resolved.
+type Resolved = BTreeMap<String, Result<String, String>>;
+
+/// Registers the screens of an inventory, `jobs` rows at a time.
+///
+/// Labels are looked up or created and playlists looked up before any screen is registered,
+/// so rows never race to create the same label. A failing row does not stop the others, the
+/// report tells which rows failed and why.
+pub fn import(
+    authentication: &Authentication,
+    rows: &[InventoryRow],
+    jobs: usize,
+) -> Result<ImportReport, CommandError> {
+    let label_ids = prepare_labels(authentication, rows);
+    let playlist_ids = resolve_playlists(authentication, rows);
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs.max(1))
+        .build()
+        .map_err(|e| CommandError::InitializationError(e.to_string()))?;
+    let pb = ProgressBar::new(rows.len() as u64);
+    if let Ok(template) =
+        ProgressStyle::with_template("[{elapsed_precise}] {bar:60.cyan/blue} {pos}/{len} screens")
+    {
+        pb.set_style(template);
+    }
+
+    let outcomes: Vec<RowOutcome> = pool.install(|| {
+        rows.par_iter()
+            .enumerate()
+            .map(|(index, row)| {
+                let mut screen_id = None;
+                let result = import_row(
+                    authentication,
+                    row,
+                    &label_ids,
+                    &playlist_ids,
+                    &mut screen_id,
+                );
+                pb.inc(1);
+                RowOutcome {
+                    row: index + 1,
+                    pin: row.pin.clone(),
+                    name: row.name.clone(),
+                    status: if result.is_ok() { "ok" } else { "failed" },
+                    screen_id,
+                    error: result.err(),
+                }
+            })
+            .collect()
+    });
+    pb.finish_and_clear();
+
+    Ok(ImportReport::new(serde_json::to_value(outcomes)?))
+}
+
+// Ids of all labels used by the inventory, creating the missing ones. A label that can be
+// neither found nor created only fails the rows that carry it.
+fn prepare_labels(authentication: &Authentication, rows: &[InventoryRow]) -> Resolved {
+    let names: BTreeSet<&String> = rows.iter().flat_map(|row| &row.labels).collect();
+    names
+        .into_iter()
+        .map(|name| {
+            let id = match lookup::resolve(authentication, Resource::Label, name) {
+                Err(CommandError::ResourceNotFound(..)) => label::create(authentication, name)
+                    .and_then(|created| created_id(authentication, &created.value)),
+                result => result,
+            };
+            (name.clone(), id.map_err(|e| e.to_string()))
+        })
+        .collect()
+}
+
+fn resolve_playlists(authentication: &Authentication, rows: &[InventoryRow]) -> Resolved {
+    let names: BTreeSet<&String> = rows
+        .iter()
+        .filter_map(|row| row.playlist.as_ref())
+        .collect();
+    names
+        .into_iter()
+        .map(|name| {
+            let id = lookup::resolve(authentication, Resource::Playlist, name);
+            (name.clone(), id.map_err(|e| e.to_string()))
+        })
+        .collect()
+}
+
+// A row whose labels or playlist could not be resolved is not registered at all.
+fn import_row(
+    authentication: &Authentication,
+    row: &InventoryRow,
+    label_ids: &Resolved,
+    playlist_ids: &Resolved,
+    screen_id: &mut Option<String>,
+) -> Result<(), String> {
+    let labels = row
+        .labels
+        .iter()
+        .map(|name| label_ids[name].clone())
+        .collect::<Result<Vec<_>, _>>()?;
+    let playlist = row
+        .playlist
+        .as_ref()
+        .map(|name| playlist_ids[name].clone())
+        .transpose()?;
+
+    register_screen(authentication, row, &labels, playlist.as_deref(), screen_id)
+        .map_err(|e| e.to_string())
+}
+
+fn register_screen(
+    authentication: &Authentication,
+    row: &InventoryRow,
+    label_ids: &[String],
+    playlist_id: Option<&str>,
+    screen_id: &mut Option<String>,
+) -> Result<(), CommandError> {
+    let screens = screen::add(authentication, row.pin.trim(), row.name.clone())?;
+    let id = created_id(authentication, &screens.value)?;
+    *screen_id = Some(id.clone());
+
+    if row.location.is_some() {
+        let location = ScreenUpdate {
+            location: row.location.clone(),
+            ..Default::default()
+        };
+        screen::update(authentication, &id, &location)?;
+    }
+    for label_id in label_ids {
+        label::link_screen(authentication, label_id, &id)?;
+    }
+    // Through the screen's own label, so that other screens of the row's labels are left
+    // alone.
+    if let Some(playlist_id) = playlist_id {
+        playlist::assign_screen(authentication, playlist_id, &id)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use httpmock::Method::{GET, PATCH, POST};
+    use httpmock::MockServer;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::authentication::Config;
+
+    #[test]
+    fn test_read_inventory_should_parse_csv_and_yaml() {
+        let tmp_dir = tempdir().unwrap();
+        let csv_path = tmp_dir.path().join("inventory.csv");
+        fs::write(
+            &csv_path,
+            "pin,name,labels,location,playlist\n\
+             111111,Store 1,north; flagship,\"Main St 1, Springfield\",Menus\n\
+             222222,,,,\n",
+        )
+        .unwrap();
+        let yaml_path = tmp_dir.path().join("inventory.yml");
+        fs::write(
+            &yaml_path,
+            "- pin: '111111'\n  name: Store 1\n  labels: [north, flagship]\n  location: Main St 1, Springfield\n  playlist: Menus\n- pin: '222222'\n",
+        )
+        .unwrap();
+
+        let expected = vec![
+            InventoryRow {
+                pin: "111111".to_string(),
+                name: Some("Store 1".to_string()),
+                labels: vec!["north".to_string(), "flagship".to_string()],
+                location: Some("Main St 1, Springfield".to_string()),
+                playlist: Some("Menus".to_string()),
+            },
+            InventoryRow {
+                pin: "222222".to_string(),
+                ..Default::default()
+            },
+        ];
+        assert_eq!(read_inventory(&csv_path).unwrap(), expected);
+        assert_eq!(read_inventory(&yaml_path).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_read_inventory_should_report_invalid_rows() {
+        let tmp_dir = tempdir().unwrap();
+        let path = tmp_dir.path().join("inventory.csv");
+        fs::write(
+            &path,
+            "pin,name,playlist\n111111,A,\n,B,\n111111,C,\n333333,D,Menus\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            read_inventory(&path).unwrap_err().to_string(),
+            "Invalid argument: row 2: pin is missing; \
+             row 3: pin 111111 is already used in row 1"
+        );
+    }
+
+    #[test]
+    fn test_import_should_register_screens_and_report_each_row() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/labels")
+                .query_param("name", "eq.north");
+            then.status(200)
+                .json_body(json!([{"id": "label-north", "name": "north"}]));
+        });
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/playlists")
+                .query_param("title", "eq.Menus");
+            then.status(200)
+                .json_body(json!([{"id": "playlist-menus", "title": "Menus"}]));
+        });
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/playlists")
+                .query_param("title", "eq.Dinner");
+            then.status(200).json_body(json!([]));
+        });
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/labels")
+                .query_param("name", "eq.screen:screen-1");
+            then.status(200).json_body(json!([]));
+        });
+        mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/v4/labels")
+                .json_body(json!({"name": "screen:screen-1"}));
+            then.status(201)
+                .json_body(json!([{"id": "label-screen-1", "name": "screen:screen-1"}]));
+        });
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/labels/screens")
+                .query_param("screen_id", "eq.screen-1");
+            then.status(200)
+                .json_body(json!([{"label_id": "label-north"}]));
+        });
+        let own_label_link = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/v4/labels/screens")
+                .json_body(json!({"label_id": "label-screen-1", "screen_id": "screen-1"}));
+            then.status(201).json_body(json!([]));
+        });
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/labels/playlists")
+                .query_param("playlist_id", "eq.playlist-menus");
+            then.status(200).json_body(json!([]));
+        });
+        let playlist_link = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/v4/labels/playlists")
+                .json_body(json!({"label_id": "label-screen-1", "playlist_id": "playlist-menus"}));
+            then.status(201).json_body(json!([]));
+        });
+        let shared_label_link = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/v4/labels/playlists")
+                .json_body(json!({"label_id": "label-north", "playlist_id": "playlist-menus"}));
+            then.status(201).json_body(json!([]));
+        });
+        let unknown_playlist_screen = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/v3/screens/")
+                .json_body(json!({"pin": "333333"}));
+            then.status(201).json_body(json!({"id": "screen-3"}));
+        });
+        mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/v3/screens/")
+                .json_body(json!({"pin": "111111", "name": "Store 1"}));
+            then.status(201)
+                .json_body(json!({"id": "screen-1", "name": "Store 1"}));
+        });
+        mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/v3/screens/")
+                .json_body(json!({"pin": "222222"}));
+            then.status(400).json_body(json!({"error": "Invalid pin"}));
+        });
+        let location = mock_server.mock(|when, then| {
+            when.method(PATCH)
+                .path("/v4/screens")
+                .query_param("id", "eq.screen-1")
+                .json_body(json!({"location": "Main St 1"}));
+            then.status(200).json_body(json!([{"id": "screen-1"}]));
+        });
+        let screen_link = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/v4/labels/screens")
+                .json_body(json!({"label_id": "label-north", "screen_id": "screen-1"}));
+            then.status(201).json_body(json!([]));
+        });
+
+        let config = Config::new(mock_server.base_url());
+        let authentication = Authentication::new_with_config(config, "token");
+        let rows = vec![
+            InventoryRow {
+                pin: "111111".to_string(),
+                name: Some("Store 1".to_string()),
+                labels: vec!["north".to_string()],
+                location: Some("Main St 1".to_string()),
+                playlist: Some("Menus".to_string()),
+            },
+            InventoryRow {
+                pin: "222222".to_string(),
+                ..Default::default()
+            },
+            InventoryRow {
+                pin: "333333".to_string(),
+                playlist: Some("Dinner".to_string()),
+                ..Default::default()
+            },
+        ];
+        let report = import(&authentication, &rows, 2).unwrap();
+
+        own_label_link.assert();
+        playlist_link.assert();
+        shared_label_link.assert_calls(0);
+        unknown_playlist_screen.assert_calls(0);
+        location.assert();
+        screen_link.assert();
+        assert_eq!(report.value[0]["status"], "ok");
+        assert_eq!(report.value[0]["screen_id"], "screen-1");
+        assert_eq!(report.value[1]["row"], 2);
+        assert_eq!(report.value[1]["status"], "failed");
+        assert!(report.value[1]["screen_id"].is_null());
+        assert!(report.value[1]["error"]
+            .as_str()
+            .unwrap()
+            .contains("Invalid pin"));
+        assert_eq!(report.value[2]["status"], "failed");
+        assert_eq!(report.value[2]["error"], "No playlist matches \"Dinner\".");
+    }
+}
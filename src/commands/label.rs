@@ -0,0 +1,307 @@
+use serde_json::json;
+
+use crate::authentication::Authentication;
+use crate::commands;
+use crate::commands::lookup::{self, Resource};
+use crate::commands::pagination::{self, Pages, Pagination};
+use crate::commands::{CommandError, Labels};
+
+pub struct LabelCommand {
+    authentication: Authentication,
+}
+
+impl LabelCommand {
+    pub fn new(authentication: Authentication) -> Self {
+        Self { authentication }
+    }
+
+    /// Resolves a label id, name or `name:<glob>` selector to an id.
+    pub fn resolve(&self, reference: &str) -> Result<String, CommandError> {
+        lookup::resolve(&self.authentication, Resource::Label, reference)
+    }
+
+    /// Looks up an id-shaped reference as a label name, for when nothing has that id.
+    pub fn resolve_id_as_name(&self, reference: &str) -> Result<Option<String>, CommandError> {
+        lookup::resolve_id_as_name(&self.authentication, Resource::Label, reference)
+    }
+
+    /// Resolves a screen id, name or `name:<glob>` selector to an id.
+    pub fn resolve_screen(&self, reference: &str) -> Result<String, CommandError> {
+        lookup::resolve(&self.authentication, Resource::Screen, reference)
+    }
+
+    /// Resolves a playlist id, title or `name:<glob>` selector to an id.
+    pub fn resolve_playlist(&self, reference: &str) -> Result<String, CommandError> {
+        lookup::resolve(&self.authentication, Resource::Playlist, reference)
+    }
+
+    /// The rows of the list command, one page at a time.
+    pub fn pages(&self, pagination: Pagination) -> Pages<'_> {
+        Pages::new(&self.authentication, "v4/labels", pagination)
+    }
+
+    pub fn create(&self, name: &str) -> Result<Labels, CommandError> {
+        create(&self.authentication, name)
+    }
+
+    pub fn update(&self, id: &str, name: &str) -> Result<Labels, CommandError> {
+        validate_name(name)?;
+        let response = commands::patch(
+            &self.authentication,
+            &format!("v4/labels?id=eq.{id}"),
+            &json!({"name": name}),
+        )?;
+        if response.as_array().is_some_and(|labels| labels.is_empty()) {
+            return Err(CommandError::ResourceNotFound(
+                "label".to_string(),
+                id.to_string(),
+            ));
+        }
+        Ok(Labels::new(response))
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), CommandError> {
+        commands::delete(&self.authentication, &format!("v4/labels?id=eq.{id}"))
+    }
+
+    pub fn link_screen(&self, label_id: &str, screen_id: &str) -> Result<(), CommandError> {
+        link_screen(&self.authentication, label_id, screen_id)
+    }
+
+    pub fn unlink_screen(&self, label_id: &str, screen_id: &str) -> Result<(), CommandError> {
+        commands::delete(
+            &self.authentication,
+            &format!("v4/labels/screens?label_id=eq.{label_id}&screen_id=eq.{screen_id}"),
+        )
+    }
+
+    pub fn link_playlist(&self, label_id: &str, playlist_id: &str) -> Result<(), CommandError> {
+        link_playlist(&self.authentication, label_id, playlist_id)
+    }
+
+    pub fn unlink_playlist(&self, label_id: &str, playlist_id: &str) -> Result<(), CommandError> {
+        commands::delete(
+            &self.authentication,
+            &format!("v4/labels/playlists?label_id=eq.{label_id}&playlist_id=eq.{playlist_id}"),
+        )
+    }
+}
+
+pub fn create(authentication: &Authentication, name: &str) -> Result<Labels, CommandError> {
+    validate_name(name)?;
+    let response = commands::post(authentication, "v4/labels", &json!({"name": name}))?;
+    Ok(Labels::new(response))
+}
+
+pub fn link_screen(
+    authentication: &Authentication,
+    label_id: &str,
+    screen_id: &str,
+) -> Result<(), CommandError> {
+    commands::post(
+        authentication,
+        "v4/labels/screens",
+        &json!({"label_id": label_id, "screen_id": screen_id}),
+    )?;
+    Ok(())
+}
+
+pub fn link_playlist(
+    authentication: &Authentication,
+    label_id: &str,
+    playlist_id: &str,
+) -> Result<(), CommandError> {
+    commands::post(
+        authentication,
+        "v4/labels/playlists",
+        &json!({"label_id": label_id, "playlist_id": playlist_id}),
+    )?;
+    Ok(())
+}
+
+/// Ids of the labels a playlist is linked to.
+pub fn playlist_label_ids(
+    authentication: &Authentication,
+    playlist_id: &str,
+) -> Result<Vec<String>, CommandError> {
+    let links = pagination::get_all(
+        authentication,
+        &format!("v4/labels/playlists?select=label_id&playlist_id=eq.{playlist_id}"),
+        Pagination::default(),
+    )?;
+    Ok(column(&links, "label_id"))
+}
+
+/// Ids of the labels a screen carries.
+pub fn screen_label_ids(
+    authentication: &Authentication,
+    screen_id: &str,
+) -> Result<Vec<String>, CommandError> {
+    let links = pagination::get_all(
+        authentication,
+        &format!("v4/labels/screens?select=label_id&screen_id=eq.{screen_id}"),
+        Pagination::default(),
+    )?;
+    Ok(column(&links, "label_id"))
+}
+
+/// Ids of the screens that carry the label.
+pub fn screen_ids(
+    authentication: &Authentication,
+    label_id: &str,
+) -> Result<Vec<String>, CommandError> {
+    let links = pagination::get_all(
+        authentication,
+        &format!("v4/labels/screens?select=screen_id&label_id=eq.{label_id}"),
+        Pagination::default(),
+    )?;
+    Ok(column(&links, "screen_id"))
+}
+
+fn column(rows: &serde_json::Value, field: &str) -> Vec<String> {
+    rows.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|row| row[field].as_str().map(str::to_string))
+        .collect()
+}
+
+fn validate_name(name: &str) -> Result<(), CommandError> {
+    if name.trim().is_empty() {
+        return Err(CommandError::InvalidArgument(
+            "label name cannot be empty".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use httpmock::Method::{DELETE, GET, PATCH, POST};
+    use httpmock::MockServer;
+    use serde_json::json;
+
+    use super::*;
+    use crate::authentication::Config;
+    use crate::commands::{FormatOptions, Formatter, OutputType};
+
+    fn label_command(mock_server: &MockServer) -> LabelCommand {
+        let config = Config::new(mock_server.base_url());
+        LabelCommand::new(Authentication::new_with_config(config, "token"))
+    }
+
+    #[test]
+    fn test_create_and_update_label_should_send_name() {
+        let mock_server = MockServer::start();
+        let post_mock = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/v4/labels")
+                .header("Authorization", "Token token")
+                .json_body(json!({"name": "Lobby"}));
+            then.status(201)
+                .json_body(json!([{"id": "01H2QZ6Z8WXWNDC0KQ198XCZEL", "name": "Lobby"}]));
+        });
+        let patch_mock = mock_server.mock(|when, then| {
+            when.method(PATCH)
+                .path("/v4/labels")
+                .query_param("id", "eq.01H2QZ6Z8WXWNDC0KQ198XCZEL")
+                .json_body(json!({"name": "Entrance"}));
+            then.status(200)
+                .json_body(json!([{"id": "01H2QZ6Z8WXWNDC0KQ198XCZEL", "name": "Entrance"}]));
+        });
+        let command = label_command(&mock_server);
+
+        let created = command.create("Lobby").unwrap();
+        post_mock.assert();
+        assert_eq!(created.value[0]["name"], "Lobby");
+
+        let updated = command
+            .update("01H2QZ6Z8WXWNDC0KQ198XCZEL", "Entrance")
+            .unwrap();
+        patch_mock.assert();
+        assert_eq!(updated.value[0]["name"], "Entrance");
+
+        assert!(matches!(
+            command.create(" "),
+            Err(CommandError::InvalidArgument(_))
+        ));
+        post_mock.assert_calls(1);
+    }
+
+    #[test]
+    fn test_link_and_unlink_should_use_join_endpoints() {
+        let mock_server = MockServer::start();
+        let link_screen = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/v4/labels/screens")
+                .json_body(json!({"label_id": "label", "screen_id": "screen"}));
+            then.status(201).json_body(json!([]));
+        });
+        let unlink_screen = mock_server.mock(|when, then| {
+            when.method(DELETE)
+                .path("/v4/labels/screens")
+                .query_param("label_id", "eq.label")
+                .query_param("screen_id", "eq.screen");
+            then.status(204);
+        });
+        let link_playlist = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/v4/labels/playlists")
+                .json_body(json!({"label_id": "label", "playlist_id": "playlist"}));
+            then.status(201).json_body(json!([]));
+        });
+        let unlink_playlist = mock_server.mock(|when, then| {
+            when.method(DELETE)
+                .path("/v4/labels/playlists")
+                .query_param("label_id", "eq.label")
+                .query_param("playlist_id", "eq.playlist");
+            then.status(204);
+        });
+        let command = label_command(&mock_server);
+
+        command.link_screen("label", "screen").unwrap();
+        command.unlink_screen("label", "screen").unwrap();
+        command.link_playlist("label", "playlist").unwrap();
+        command.unlink_playlist("label", "playlist").unwrap();
+
+        link_screen.assert();
+        unlink_screen.assert();
+        link_playlist.assert();
+        unlink_playlist.assert();
+    }
+
+    #[test]
+    fn test_screen_ids_should_return_linked_screens() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/labels/screens")
+                .query_param("select", "screen_id")
+                .query_param("label_id", "eq.label");
+            then.status(200)
+                .json_body(json!([{"screen_id": "screen-1"}, {"screen_id": "screen-2"}]));
+        });
+
+        let config = Config::new(mock_server.base_url());
+        let authentication = Authentication::new_with_config(config, "token");
+        let ids = screen_ids(&authentication, "label").unwrap();
+        assert_eq!(ids, vec!["screen-1", "screen-2"]);
+    }
+
+    #[test]
+    fn test_format_labels_should_print_id_and_name() {
+        let labels = Labels::new(json!([{"id": "01H2QZ6Z8WXWNDC0KQ198XCZEL", "name": "Lobby"}]));
+
+        let expected_output = "+----------------------------+-------+\n\
+| Id                         | Name  |\n\
++----------------------------+-------+\n\
+| 01H2QZ6Z8WXWNDC0KQ198XCZEL | Lobby |\n\
++----------------------------+-------+\n";
+        assert_eq!(
+            labels
+                .format_with(&FormatOptions::new(OutputType::HumanReadable))
+                .unwrap(),
+            expected_output
+        );
+    }
+}
@@ -0,0 +1,363 @@
+use glob::Pattern;
+
+use crate::authentication::Authentication;
+use crate::commands;
+use crate::commands::pagination::{self, Pagination};
+use crate::commands::CommandError;
+
+/// Resources that commands can refer to by id, name or `name:<glob>` selector.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resource {
+    Screen,
+    Asset,
+    Playlist,
+    Label,
+    AssetGroup,
+    Team,
+}
+
+impl Resource {
+    fn label(self) -> &'static str {
+        match self {
+            Resource::Screen => "screen",
+            Resource::Asset => "asset",
+            Resource::Playlist => "playlist",
+            Resource::Label => "label",
+            Resource::AssetGroup => "asset group",
+            Resource::Team => "team",
+        }
+    }
+
+    fn endpoint(self) -> &'static str {
+        match self {
+            Resource::Screen => "v4/screens",
+            Resource::Asset => "v4/assets",
+            Resource::Playlist => "v4/playlists",
+            Resource::Label => "v4/labels",
+            Resource::AssetGroup => "v4/asset-groups",
+            Resource::Team => "v4/teams",
+        }
+    }
+
+    /// Filters that the list command of the resource applies as well.
+    fn filters(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Resource::Asset => &[("type", "neq.edge-app-file")],
+            _ => &[],
+        }
+    }
+
+    /// Screens, labels and teams have names, assets, asset groups and playlists have titles.
+    fn name_field(self) -> &'static str {
+        match self {
+            Resource::Screen | Resource::Label | Resource::Team => "name",
+            Resource::Asset | Resource::AssetGroup | Resource::Playlist => "title",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Reference {
+    Id(String),
+    Name(String),
+    Pattern(Pattern),
+}
+
+impl Reference {
+    fn parse(reference: &str) -> Result<Self, CommandError> {
+        if let Some(pattern) = reference.strip_prefix("name:") {
+            return Ok(Reference::Pattern(Pattern::new(pattern)?));
+        }
+        if is_id(reference) {
+            return Ok(Reference::Id(reference.to_string()));
+        }
+        Ok(Reference::Name(reference.to_string()))
+    }
+}
+
+/// Turns a resource argument into an id. Names and `name:<glob>` selectors are looked up
+/// and must match exactly one resource. Ids are used as they are without a request; when
+/// nothing has such an id, [`resolve_id_as_name`] looks it up as a name instead.
+pub fn resolve(
+    authentication: &Authentication,
+    resource: Resource,
+    reference: &str,
+) -> Result<String, CommandError> {
+    let field = resource.name_field();
+    let matches = match Reference::parse(reference)? {
+        Reference::Id(id) => return Ok(id),
+        Reference::Name(name) => find_by_name(authentication, resource, &name)?,
+        Reference::Pattern(pattern) => {
+            let select = format!("id,{field}");
+            let endpoint = query(
+                resource.endpoint(),
+                &[&[("select", select.as_str())], resource.filters()].concat(),
+            );
+            rows(pagination::get_all(
+                authentication,
+                &endpoint,
+                Pagination::default(),
+            )?)
+            .into_iter()
+            .filter(|row| {
+                row[field]
+                    .as_str()
+                    .is_some_and(|name| pattern.matches(name))
+            })
+            .collect()
+        }
+    };
+    single(resource, reference, matches)
+}
+
+/// Looks up an id-shaped reference as a name, since a name may look like an id. Meant for
+/// when a command finds nothing with the id. Returns `None` for other references and for
+/// names that match nothing either.
+pub fn resolve_id_as_name(
+    authentication: &Authentication,
+    resource: Resource,
+    reference: &str,
+) -> Result<Option<String>, CommandError> {
+    if !is_id(reference) {
+        return Ok(None);
+    }
+    let matches = find_by_name(authentication, resource, reference)?;
+    if matches.is_empty() {
+        return Ok(None);
+    }
+    single(resource, reference, matches).map(Some)
+}
+
+fn single(
+    resource: Resource,
+    reference: &str,
+    matches: Vec<serde_json::Value>,
+) -> Result<String, CommandError> {
+    let field = resource.name_field();
+    match matches.as_slice() {
+        [] => Err(CommandError::ResourceNotFound(
+            resource.label().to_string(),
+            reference.to_string(),
+        )),
+        [row] => row["id"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or(CommandError::MissingField),
+        rows => {
+            let candidates = rows
+                .iter()
+                .map(|row| {
+                    format!(
+                        "{} ({})",
+                        row[field].as_str().unwrap_or_default(),
+                        row["id"].as_str().unwrap_or_default()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            Err(CommandError::AmbiguousReference(
+                reference.to_string(),
+                rows.len(),
+                resource.label().to_string(),
+                candidates,
+            ))
+        }
+    }
+}
+
+fn find_by_name(
+    authentication: &Authentication,
+    resource: Resource,
+    name: &str,
+) -> Result<Vec<serde_json::Value>, CommandError> {
+    let field = resource.name_field();
+    let select = format!("id,{field}");
+    let name = format!("eq.{name}");
+    let endpoint = query(
+        resource.endpoint(),
+        &[
+            &[("select", select.as_str()), (field, name.as_str())],
+            resource.filters(),
+        ]
+        .concat(),
+    );
+    Ok(rows(commands::get(authentication, &endpoint)?))
+}
+
+// Appends the percent-encoded query parameters to the endpoint.
+fn query(endpoint: &str, pairs: &[(&str, &str)]) -> String {
+    // Only used to percent-encode the query.
+    let mut url = reqwest::Url::parse("http://localhost/").expect("valid URL");
+    url.query_pairs_mut().extend_pairs(pairs);
+    format!("{endpoint}?{}", url.query().unwrap_or_default())
+}
+
+fn rows(value: serde_json::Value) -> Vec<serde_json::Value> {
+    match value {
+        serde_json::Value::Array(rows) => rows,
+        _ => Vec::new(),
+    }
+}
+
+// Screenly ids are ULIDs. Plain UUIDs are accepted as well.
+pub fn is_id(reference: &str) -> bool {
+    let is_ulid = reference.len() == 26
+        && reference
+            .chars()
+            .all(|c| c.is_ascii_digit() || (c.is_ascii_uppercase() && !"ILOU".contains(c)));
+    let is_uuid = reference.len() == 36
+        && reference.chars().enumerate().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        });
+    is_ulid || is_uuid
+}
+
+#[cfg(test)]
+mod tests {
+    use httpmock::Method::GET;
+    use httpmock::MockServer;
+    use serde_json::json;
+
+    use super::*;
+    use crate::authentication::Config;
+
+    fn authentication(mock_server: &MockServer) -> Authentication {
+        Authentication::new_with_config(Config::new(mock_server.base_url()), "token")
+    }
+
+    #[test]
+    fn test_resolve_when_reference_is_id_should_not_send_requests() {
+        let mock_server = MockServer::start();
+        let screens_mock = mock_server.mock(|when, then| {
+            when.method(GET).path("/v4/screens");
+            then.status(200).json_body(json!([]));
+        });
+
+        let id = resolve(
+            &authentication(&mock_server),
+            Resource::Screen,
+            "01H2QZ6Z8WXWNDC0KQ198XCZEW",
+        )
+        .unwrap();
+        assert_eq!(id, "01H2QZ6Z8WXWNDC0KQ198XCZEW");
+        screens_mock.assert_calls(0);
+    }
+
+    #[test]
+    fn test_resolve_id_as_name_should_look_up_id_shaped_names_only() {
+        let mock_server = MockServer::start();
+        let name_mock = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/assets")
+                .query_param("title", "eq.BREAKFAST2026WEEKDAYSMENVS")
+                .query_param("type", "neq.edge-app-file");
+            then.status(200).json_body(
+                json!([{"id": "01H2QZ6Z8WXWNDC0KQ198XCZEA", "title": "BREAKFAST2026WEEKDAYSMENVS"}]),
+            );
+        });
+        let missing_mock = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/assets")
+                .query_param("title", "eq.01H2QZ6Z8WXWNDC0KQ198XCZEB");
+            then.status(200).json_body(json!([]));
+        });
+        let authentication = authentication(&mock_server);
+
+        let id = resolve_id_as_name(
+            &authentication,
+            Resource::Asset,
+            "BREAKFAST2026WEEKDAYSMENVS",
+        )
+        .unwrap();
+        assert_eq!(id.as_deref(), Some("01H2QZ6Z8WXWNDC0KQ198XCZEA"));
+        let id = resolve_id_as_name(
+            &authentication,
+            Resource::Asset,
+            "01H2QZ6Z8WXWNDC0KQ198XCZEB",
+        )
+        .unwrap();
+        assert_eq!(id, None);
+        let id = resolve_id_as_name(&authentication, Resource::Asset, "Breakfast").unwrap();
+        assert_eq!(id, None);
+
+        name_mock.assert();
+        missing_mock.assert();
+    }
+
+    #[test]
+    fn test_resolve_should_find_screen_by_exact_name() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/screens")
+                .query_param("select", "id,name")
+                .query_param("name", "eq.Lobby & Hall");
+            then.status(200)
+                .json_body(json!([{"id": "01H2QZ6Z8WXWNDC0KQ198XCZEW", "name": "Lobby & Hall"}]));
+        });
+
+        let id = resolve(
+            &authentication(&mock_server),
+            Resource::Screen,
+            "Lobby & Hall",
+        )
+        .unwrap();
+        assert_eq!(id, "01H2QZ6Z8WXWNDC0KQ198XCZEW");
+    }
+
+    #[test]
+    fn test_resolve_should_match_asset_titles_against_glob() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/assets")
+                .query_param("select", "id,title")
+                .query_param("type", "neq.edge-app-file");
+            then.status(200).json_body(json!([
+                {"id": "01H2QZ6Z8WXWNDC0KQ198XCZEA", "title": "Menu - breakfast"},
+                {"id": "01H2QZ6Z8WXWNDC0KQ198XCZEB", "title": "Menu - lunch"},
+                {"id": "01H2QZ6Z8WXWNDC0KQ198XCZEC", "title": "Welcome"},
+            ]));
+        });
+        let authentication = authentication(&mock_server);
+
+        let id = resolve(&authentication, Resource::Asset, "name:Wel*").unwrap();
+        assert_eq!(id, "01H2QZ6Z8WXWNDC0KQ198XCZEC");
+
+        let error = resolve(&authentication, Resource::Asset, "name:Menu*").unwrap_err();
+        assert!(matches!(error, CommandError::AmbiguousReference(..)));
+        assert_eq!(
+            error.to_string(),
+            "\"name:Menu*\" matches 2 assets: Menu - breakfast (01H2QZ6Z8WXWNDC0KQ198XCZEA), \
+             Menu - lunch (01H2QZ6Z8WXWNDC0KQ198XCZEB). Use an id or a more specific name."
+        );
+
+        let error = resolve(&authentication, Resource::Asset, "name:Dinner*").unwrap_err();
+        assert_eq!(error.to_string(), "No asset matches \"name:Dinner*\".");
+    }
+
+    #[test]
+    fn test_reference_parse_should_tell_ids_names_and_patterns_apart() {
+        assert_eq!(
+            Reference::parse("01H2QZ6Z8WXWNDC0KQ198XCZEW").unwrap(),
+            Reference::Id("01H2QZ6Z8WXWNDC0KQ198XCZEW".to_string())
+        );
+        assert_eq!(
+            Reference::parse("5f1b2c3d-0a1b-4c5d-8e9f-0a1b2c3d4e5f").unwrap(),
+            Reference::Id("5f1b2c3d-0a1b-4c5d-8e9f-0a1b2c3d4e5f".to_string())
+        );
+        assert_eq!(
+            Reference::parse("Lobby").unwrap(),
+            Reference::Name("Lobby".to_string())
+        );
+        assert_eq!(
+            Reference::parse("name:Lobby*").unwrap(),
+            Reference::Pattern(Pattern::new("Lobby*").unwrap())
+        );
+        assert!(matches!(
+            Reference::parse("name:[Lobby"),
+            Err(CommandError::Pattern(_))
+        ));
+    }
+}
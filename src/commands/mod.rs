@@ -2,62 +2,323 @@ use std::time::Duration;
 
 use log::debug;
 use prettytable::{cell, Cell, Row};
+use reqwest::blocking::Response;
 use reqwest::header::{HeaderMap, InvalidHeaderValue};
-use reqwest::StatusCode;
+use reqwest::{Method, StatusCode};
 use serde::{Deserialize, Deserializer, Serialize};
 use thiserror::Error;
 
 use crate::api::edge_app::app::EdgeApps;
 use crate::api::edge_app::installation::EdgeAppInstances;
+use crate::commands::retry::send_with_retry;
 use crate::{Authentication, AuthenticationError};
 
 pub mod asset;
+pub mod asset_group;
+pub mod cassette;
+pub mod completions;
 pub mod edge_app;
+pub mod health;
 
 mod ignorer;
+pub mod inventory;
+pub mod label;
+pub mod lookup;
+pub mod pagination;
 pub(crate) mod playlist;
+pub mod predicate;
+pub mod predicate_builder;
+pub mod profile;
+pub mod retry;
+pub mod schedule;
 pub mod screen;
 pub(crate) mod serde_utils;
+pub mod whoami;
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
 pub enum OutputType {
+    /// A human-readable table.
+    #[default]
+    #[value(name = "table")]
     HumanReadable,
+    /// Pretty-printed JSON.
     Json,
+    /// YAML.
+    Yaml,
+    /// Comma-separated values with the table columns.
+    Csv,
+    /// One JSON document per line.
+    Ndjson,
+    /// One id per line, e.g. for piping into `xargs`.
+    Ids,
 }
 
 pub trait Formatter {
-    fn format(&self, output_type: OutputType) -> String;
+    fn format_with(&self, options: &FormatOptions) -> Result<String, CommandError>;
 }
 
 pub trait FormatterValue {
     fn value(&self) -> &serde_json::Value;
 }
 
+/// How the rows returned by a command are selected and presented.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FormatOptions {
+    pub output_type: OutputType,
+    /// Fields to show instead of the default columns.
+    pub columns: Option<Vec<String>>,
+    /// Field to sort rows by. A leading `-` sorts in descending order.
+    pub sort_by: Option<String>,
+    pub filters: Vec<RowFilter>,
+    /// Replaces the output format when set.
+    pub template: Option<OutputTemplate>,
+}
+
+/// Whether a template is rendered once per row or once for the whole collection.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum TemplateScope {
+    /// Renders every row with its fields as variables, plus `item` and `index`.
+    #[default]
+    Item,
+    /// Renders the template once with the rows as `items`.
+    Collection,
+}
+
+/// A Jinja-style template rendered with the API response as context.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutputTemplate {
+    source: String,
+    scope: TemplateScope,
+}
+
+impl OutputTemplate {
+    /// Accepts the template itself or `@path` to read it from a file.
+    pub fn new(template: &str, scope: TemplateScope) -> Result<Self, CommandError> {
+        let source = match template.strip_prefix('@') {
+            Some(path) => std::fs::read_to_string(path)?,
+            None => template.to_string(),
+        };
+        minijinja::Environment::new().template_from_str(&source)?;
+        Ok(Self { source, scope })
+    }
+
+    fn render(&self, value: &serde_json::Value) -> Result<String, minijinja::Error> {
+        let env = minijinja::Environment::new();
+        let template = env.template_from_str(&self.source)?;
+        match self.scope {
+            TemplateScope::Collection => {
+                let items = rows(value).collect::<Vec<_>>();
+                template.render(minijinja::context! { items => items })
+            }
+            TemplateScope::Item => rows(value)
+                .enumerate()
+                .map(|(index, item)| {
+                    let context = minijinja::context! { item => item, index => index };
+                    match item {
+                        serde_json::Value::Object(_) => template.render(minijinja::context! {
+                            ..minijinja::Value::from_serialize(item),
+                            ..context
+                        }),
+                        _ => template.render(context),
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(|lines| lines.join("\n")),
+        }
+    }
+}
+
+impl FormatOptions {
+    pub fn new(output_type: OutputType) -> Self {
+        Self {
+            output_type,
+            ..Default::default()
+        }
+    }
+
+    /// Whether rows can be printed page by page as they are fetched. Every row of ndjson and
+    /// ids output is a line of its own, unless sorting or a template needs all of them.
+    pub fn streams(&self) -> bool {
+        matches!(self.output_type, OutputType::Ndjson | OutputType::Ids)
+            && self.sort_by.is_none()
+            && self.template.is_none()
+    }
+
+    // Filtering and sorting happen client-side because not every endpoint supports them.
+    fn apply(&self, value: &serde_json::Value) -> serde_json::Value {
+        if self.filters.is_empty() && self.sort_by.is_none() {
+            return value.clone();
+        }
+
+        let matchers = self
+            .filters
+            .iter()
+            .map(RowFilter::matcher)
+            .collect::<Vec<_>>();
+        let mut filtered = rows(value)
+            .filter(|row| matchers.iter().all(|matches| matches(row)))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if let Some(sort_by) = &self.sort_by {
+            let (field, descending) = match sort_by.strip_prefix('-') {
+                Some(field) => (field, true),
+                None => (sort_by.as_str(), false),
+            };
+            filtered.sort_by(|a, b| compare_values(&a[field], &b[field]));
+            if descending {
+                filtered.reverse();
+            }
+        }
+        serde_json::Value::Array(filtered)
+    }
+}
+
+/// Keeps rows whose field equals a value (`key=value`, case-insensitive) or matches a regular
+/// expression (`key~regex`).
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RowFilter {
+    pub field: String,
+    pub pattern: String,
+    pub regex: bool,
+}
+
+impl std::str::FromStr for RowFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (pos, regex) = match (s.find('='), s.find('~')) {
+            (Some(eq), Some(tilde)) if tilde < eq => (tilde, true),
+            (Some(eq), _) => (eq, false),
+            (None, Some(tilde)) => (tilde, true),
+            (None, None) => {
+                return Err(format!(
+                    "invalid filter `{s}`: expected `key=value` or `key~regex`"
+                ))
+            }
+        };
+
+        let filter = Self {
+            field: s[..pos].to_string(),
+            pattern: s[pos + 1..].to_string(),
+            regex,
+        };
+        if filter.field.is_empty() {
+            return Err(format!("invalid filter `{s}`: field name is missing"));
+        }
+        if regex {
+            regex::Regex::new(&filter.pattern).map_err(|e| e.to_string())?;
+        }
+        Ok(filter)
+    }
+}
+
+impl RowFilter {
+    fn matcher(&self) -> Box<dyn Fn(&serde_json::Value) -> bool + '_> {
+        if self.regex {
+            // The pattern is validated when the filter is parsed.
+            let regex = regex::Regex::new(&self.pattern).unwrap();
+            Box::new(move |row| regex.is_match(&display_text(&row[&self.field])))
+        } else {
+            Box::new(|row| display_text(&row[&self.field]).eq_ignore_ascii_case(&self.pattern))
+        }
+    }
+}
+
+fn display_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+// Numbers compare numerically and missing values sort last.
+fn compare_values(a: &serde_json::Value, b: &serde_json::Value) -> std::cmp::Ordering {
+    use serde_json::Value;
+    use std::cmp::Ordering;
+
+    match (a, b) {
+        (Value::Null, Value::Null) => Ordering::Equal,
+        (Value::Null, _) => Ordering::Greater,
+        (_, Value::Null) => Ordering::Less,
+        (Value::Number(a), Value::Number(b)) => a
+            .as_f64()
+            .partial_cmp(&b.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (a, b) => display_text(a).cmp(&display_text(b)),
+    }
+}
+
 // Helper function to format a value returned from the API.
 // Can be used if there is no need to make any transformation on the returned value.
 fn format_value<T, F>(
-    output_type: OutputType,
+    options: &FormatOptions,
     column_names: Vec<&str>,
     field_names: Vec<&str>,
     value: &T,
     value_transformer: Option<F>,
-) -> String
+) -> Result<String, CommandError>
 where
     T: FormatterValue,
     F: Fn(&str, &serde_json::Value) -> Cell, // Takes field name and field value and returns display representation
 {
-    match output_type {
+    let value = options.apply(value.value());
+    if let Some(template) = &options.template {
+        return Ok(template.render(&value)?);
+    }
+    let default_fields = field_names.clone();
+
+    // Selected columns are matched by field or column name. Other fields of the
+    // response are shown under their own name.
+    let (column_names, field_names): (Vec<&str>, Vec<&str>) = match &options.columns {
+        Some(columns) => columns
+            .iter()
+            .map(|column| {
+                field_names
+                    .iter()
+                    .zip(&column_names)
+                    .find(|(field, name)| *field == column || name.eq_ignore_ascii_case(column))
+                    .map(|(field, name)| (*name, *field))
+                    .unwrap_or((column.as_str(), column.as_str()))
+            })
+            .unzip(),
+        None => (column_names, field_names),
+    };
+    let projected = || -> serde_json::Value {
+        if options.columns.is_none() {
+            return value.clone();
+        }
+        let select = |row: &serde_json::Value| {
+            serde_json::Value::Object(
+                field_names
+                    .iter()
+                    .map(|field| (field.to_string(), row[field].clone()))
+                    .collect(),
+            )
+        };
+        match &value {
+            serde_json::Value::Array(rows) => rows.iter().map(select).collect(),
+            row => select(row),
+        }
+    };
+
+    let output = match options.output_type {
         OutputType::HumanReadable => {
             let mut table = prettytable::Table::new();
             table.add_row(Row::from(column_names));
 
-            if let Some(values) = value.value().as_array() {
+            if let Some(values) = value.as_array() {
                 for v in values {
                     let mut row_content = Vec::new();
                     for field in &field_names {
-                        let display_value = if let Some(transformer) = &value_transformer {
-                            transformer(field, &v[field])
-                        } else {
-                            Cell::new(v[field].as_str().unwrap_or("N/A"))
+                        let display_value = match &value_transformer {
+                            Some(transformer) if default_fields.contains(field) => {
+                                transformer(field, &v[field])
+                            }
+                            _ if v[field].is_null() => Cell::new("N/A"),
+                            _ => Cell::new(&display_text(&v[field])),
                         };
                         row_content.push(display_value);
                     }
@@ -66,7 +327,55 @@ where
             }
             table.to_string()
         }
-        OutputType::Json => serde_json::to_string_pretty(&value.value()).unwrap(),
+        OutputType::Json => serde_json::to_string_pretty(&projected()).unwrap(),
+        OutputType::Yaml => serde_yaml::to_string(&projected()).unwrap(),
+        OutputType::Csv => {
+            let mut lines = vec![column_names
+                .iter()
+                .map(|name| csv_field(name))
+                .collect::<Vec<_>>()
+                .join(",")];
+            for v in rows(&value) {
+                let line = field_names
+                    .iter()
+                    .map(|field| csv_field(&display_text(&v[field])))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                lines.push(line);
+            }
+            lines.join("\n")
+        }
+        OutputType::Ndjson => rows(&projected())
+            .map(|v| serde_json::to_string(v).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        OutputType::Ids => rows(&value)
+            // Rows without an id, such as profiles, are identified by their first column.
+            .filter_map(|v| match v.get("id") {
+                Some(id) => Some(id),
+                None => default_fields.first().map(|field| &v[field]),
+            })
+            .map(display_text)
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+    Ok(output)
+}
+
+// Treats a single object like a collection of one row.
+fn rows(value: &serde_json::Value) -> Box<dyn Iterator<Item = &serde_json::Value> + '_> {
+    match value {
+        serde_json::Value::Array(values) => Box::new(values.iter()),
+        serde_json::Value::Null => Box::new(std::iter::empty()),
+        value => Box::new(std::iter::once(value)),
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
     }
 }
 
@@ -80,8 +389,8 @@ pub enum CommandError {
     Parse(#[from] serde_json::Error),
     #[error("parse error: {0}")]
     YamlParse(#[from] serde_yaml::Error),
-    #[error("unknown error: {0}")]
-    WrongResponseStatus(u16),
+    #[error("{0}")]
+    Api(Box<ApiError>),
     #[error("Required field is missing in the response")]
     MissingField,
     #[error("Required file is missing in the edge app directory: {0}")]
@@ -130,6 +439,111 @@ pub enum CommandError {
     MissingInstallationId,
     #[error("App not found: {0}")]
     AppNotFound(String),
+    #[error("No {0} matches \"{1}\".")]
+    ResourceNotFound(String, String),
+    #[error("\"{0}\" matches {1} {2}s: {3}. Use an id or a more specific name.")]
+    AmbiguousReference(String, usize, String, String),
+    #[error("Invalid name pattern: {0}")]
+    Pattern(#[from] glob::PatternError),
+    #[error("Record/replay error: {0}")]
+    Cassette(String),
+    #[error("Template error: {0}")]
+    Template(#[from] minijinja::Error),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("Invalid predicate: {0}")]
+    InvalidPredicate(#[from] predicate::PredicateError),
+}
+
+impl CommandError {
+    /// HTTP status of a failed API request.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            CommandError::Api(e) => Some(e.status),
+            _ => None,
+        }
+    }
+}
+
+/// A rejected API request together with the PostgREST-style error body returned for it.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ApiError {
+    pub status: u16,
+    pub method: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
+
+impl ApiError {
+    pub fn from_response(method: Method, response: Response) -> Self {
+        let status = response.status().as_u16();
+        let url = response.url().to_string();
+        let body = response.text().unwrap_or_default();
+        debug!("Response: {body:?}");
+
+        let mut error = Self {
+            status,
+            method: method.to_string(),
+            url,
+            ..Default::default()
+        };
+
+        match serde_json::from_str::<serde_json::Value>(&body) {
+            Ok(serde_json::Value::Object(fields)) => {
+                let text = |name: &str| match fields.get(name) {
+                    Some(serde_json::Value::String(s)) => Some(s.clone()),
+                    Some(serde_json::Value::Null) | None => None,
+                    Some(other) => Some(other.to_string()),
+                };
+                // v3 endpoints report errors as `detail` or `error` instead of `message`.
+                error.message = text("message")
+                    .or_else(|| text("detail"))
+                    .or_else(|| text("error"));
+                error.details = text("details");
+                error.hint = text("hint");
+                error.code = text("code");
+            }
+            _ if !body.trim().is_empty() => error.message = Some(body.trim().to_string()),
+            _ => {}
+        }
+        error
+    }
+}
+
+impl From<ApiError> for CommandError {
+    fn from(e: ApiError) -> Self {
+        CommandError::Api(Box::new(e))
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} failed with status {}",
+            self.method, self.url, self.status
+        )?;
+        if let Some(message) = &self.message {
+            write!(f, ": {message}")?;
+        }
+        if let Some(details) = &self.details {
+            write!(f, "\nDetails: {details}")?;
+        }
+        if let Some(hint) = &self.hint {
+            write!(f, "\nHint: {hint}")?;
+        }
+        if let Some(code) = &self.code {
+            write!(f, "\nCode: {code}")?;
+        }
+        Ok(())
+    }
 }
 
 pub fn get(
@@ -140,44 +554,82 @@ pub fn get(
     let mut headers = HeaderMap::new();
     headers.insert("Prefer", "return=representation".parse()?);
 
-    let response = authentication
-        .build_client()?
-        .get(url)
-        .headers(headers)
-        .send()?;
+    let client = authentication.build_client()?;
+    let response = send_with_retry(authentication, true, || {
+        Ok(client.get(&url).headers(headers.clone()))
+    })?;
 
     let status = response.status();
 
     if status != StatusCode::OK {
-        println!("Response: {:?}", &response.text());
-        return Err(CommandError::WrongResponseStatus(status.as_u16()));
+        return Err(ApiError::from_response(Method::GET, response).into());
     }
     Ok(serde_json::from_str(&response.text()?)?)
 }
 
+/// Prints a request that `--dry-run` keeps from being sent to stderr, whatever the log
+/// level, on one line so that concurrent requests don't interleave. The payload is returned in place of the representation the
+/// API would have responded with.
+pub fn dry_run(
+    method: Method,
+    url: &str,
+    payload: Option<serde_json::Value>,
+) -> Result<serde_json::Value, CommandError> {
+    match payload {
+        Some(payload) => {
+            eprintln!("[dry-run] {method} {url} {payload}");
+            Ok(serde_json::Value::Array(vec![payload]))
+        }
+        None => {
+            eprintln!("[dry-run] {method} {url}");
+            Ok(serde_json::Value::Null)
+        }
+    }
+}
+
+/// Stands in for the id of a resource that `--dry-run` kept from being created.
+pub const DRY_RUN_ID: &str = "<dry-run>";
+
+/// Id of the first resource in the representation returned by [`post`].
+pub fn created_id(
+    authentication: &Authentication,
+    value: &serde_json::Value,
+) -> Result<String, CommandError> {
+    if authentication.config.dry_run {
+        return Ok(DRY_RUN_ID.to_string());
+    }
+    value[0]["id"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or(CommandError::MissingField)
+}
+
 pub fn post<T: Serialize + ?Sized>(
     authentication: &Authentication,
     endpoint: &str,
     payload: &T,
 ) -> Result<serde_json::Value, CommandError> {
     let url = format!("{}/{}", &authentication.config.url, endpoint);
+    if authentication.config.dry_run {
+        return dry_run(Method::POST, &url, Some(serde_json::to_value(payload)?));
+    }
     let mut headers = HeaderMap::new();
     headers.insert("Prefer", "return=representation".parse()?);
 
-    let response = authentication
-        .build_client()?
-        .post(url)
-        .headers(headers)
-        .timeout(Duration::from_secs(60))
-        .json(&payload)
-        .send()?;
+    let client = authentication.build_client()?;
+    let response = send_with_retry(authentication, false, || {
+        Ok(client
+            .post(&url)
+            .headers(headers.clone())
+            .timeout(Duration::from_secs(60))
+            .json(&payload))
+    })?;
 
     let status = response.status();
 
     // Ok, No_Content are acceptable because some of our RPC code returns that.
     if ![StatusCode::CREATED, StatusCode::OK, StatusCode::NO_CONTENT].contains(&status) {
-        debug!("Response: {:?}", &response.text()?);
-        return Err(CommandError::WrongResponseStatus(status.as_u16()));
+        return Err(ApiError::from_response(Method::POST, response).into());
     }
     if status == StatusCode::NO_CONTENT {
         return Ok(serde_json::Value::Null);
@@ -188,13 +640,17 @@ pub fn post<T: Serialize + ?Sized>(
 
 pub fn delete(authentication: &Authentication, endpoint: &str) -> anyhow::Result<(), CommandError> {
     let url = format!("{}/{}", &authentication.config.url, endpoint);
-    let response = authentication.build_client()?.delete(url).send()?;
+    if authentication.config.dry_run {
+        dry_run(Method::DELETE, &url, None)?;
+        return Ok(());
+    }
+    let client = authentication.build_client()?;
+    let response = send_with_retry(authentication, true, || Ok(client.delete(&url)))?;
 
     let status = response.status();
 
     if ![StatusCode::OK, StatusCode::NO_CONTENT].contains(&status) {
-        debug!("Response: {:?}", &response.text()?);
-        return Err(CommandError::WrongResponseStatus(status.as_u16()));
+        return Err(ApiError::from_response(Method::DELETE, response).into());
     }
     Ok(())
 }
@@ -205,20 +661,20 @@ pub fn patch<T: Serialize + ?Sized>(
     payload: &T,
 ) -> anyhow::Result<serde_json::Value, CommandError> {
     let url = format!("{}/{}", &authentication.config.url, endpoint);
+    if authentication.config.dry_run {
+        return dry_run(Method::PATCH, &url, Some(serde_json::to_value(payload)?));
+    }
     let mut headers = HeaderMap::new();
     headers.insert("Prefer", "return=representation".parse()?);
 
-    let response = authentication
-        .build_client()?
-        .patch(url)
-        .json(&payload)
-        .headers(headers)
-        .send()?;
+    let client = authentication.build_client()?;
+    let response = send_with_retry(authentication, false, || {
+        Ok(client.patch(&url).json(&payload).headers(headers.clone()))
+    })?;
 
     let status = response.status();
     if status != StatusCode::OK {
-        debug!("Response: {:?}", &response.text()?);
-        return Err(CommandError::WrongResponseStatus(status.as_u16()));
+        return Err(ApiError::from_response(Method::PATCH, response).into());
     }
 
     if status == StatusCode::NO_CONTENT {
@@ -285,9 +741,9 @@ impl FormatterValue for EdgeApps {
 }
 
 impl Formatter for EdgeApps {
-    fn format(&self, output_type: OutputType) -> String {
+    fn format_with(&self, options: &FormatOptions) -> Result<String, CommandError> {
         format_value(
-            output_type,
+            options,
             vec!["Id", "Title"],
             vec!["id", "name"],
             self,
@@ -314,9 +770,9 @@ impl FormatterValue for EdgeAppSettings {
 }
 
 impl Formatter for EdgeAppSettings {
-    fn format(&self, output_type: OutputType) -> String {
+    fn format_with(&self, options: &FormatOptions) -> Result<String, CommandError> {
         format_value(
-            output_type,
+            options,
             vec![
                 "Name",
                 "Title",
@@ -365,9 +821,9 @@ impl FormatterValue for EdgeAppInstances {
 }
 
 impl Formatter for EdgeAppInstances {
-    fn format(&self, output_type: OutputType) -> String {
+    fn format_with(&self, options: &FormatOptions) -> Result<String, CommandError> {
         format_value(
-            output_type,
+            options,
             vec!["Id", "Name"],
             vec!["id", "name"],
             self,
@@ -398,9 +854,9 @@ impl FormatterValue for Assets {
 }
 
 impl Formatter for Assets {
-    fn format(&self, output_type: OutputType) -> String {
+    fn format_with(&self, options: &FormatOptions) -> Result<String, CommandError> {
         format_value(
-            output_type,
+            options,
             vec!["Id", "Title", "Type", "Status"],
             vec!["id", "title", "type", "status"],
             self,
@@ -426,18 +882,19 @@ impl FormatterValue for Screens {
     }
 }
 
-impl Formatter for Screens {
-    fn format(&self, output_type: OutputType) -> String {
-        fn format_boolean_field(value: &serde_json::Value) -> Cell {
-            if value.as_bool().unwrap_or(false) {
-                cell!(c -> "✅")
-            } else {
-                cell!(c -> "❌")
-            }
-        }
+// Shows a boolean field as a check mark or a cross.
+fn format_boolean_field(value: &serde_json::Value) -> Cell {
+    if value.as_bool().unwrap_or(false) {
+        cell!(c -> "✅")
+    } else {
+        cell!(c -> "❌")
+    }
+}
 
+impl Formatter for Screens {
+    fn format_with(&self, options: &FormatOptions) -> Result<String, CommandError> {
         format_value(
-            output_type,
+            options,
             vec![
                 "Id",
                 "Name",
@@ -495,17 +952,9 @@ impl FormatterValue for Playlists {
 }
 
 impl Formatter for Playlists {
-    fn format(&self, output_type: OutputType) -> String {
-        fn format_boolean_field(value: &serde_json::Value) -> Cell {
-            if value.as_bool().unwrap_or(false) {
-                cell!(c -> "✅")
-            } else {
-                cell!(c -> "❌")
-            }
-        }
-
+    fn format_with(&self, options: &FormatOptions) -> Result<String, CommandError> {
         format_value(
-            output_type,
+            options,
             vec!["Id", "Title", "Enabled", "Priority"],
             vec!["id", "title", "is_enabled", "priority"],
             self,
@@ -520,6 +969,330 @@ impl Formatter for Playlists {
     }
 }
 
+#[derive(Debug)]
+pub struct AssetGroups {
+    pub value: serde_json::Value,
+}
+
+impl AssetGroups {
+    pub fn new(value: serde_json::Value) -> Self {
+        Self { value }
+    }
+}
+
+impl FormatterValue for AssetGroups {
+    fn value(&self) -> &serde_json::Value {
+        &self.value
+    }
+}
+
+impl Formatter for AssetGroups {
+    fn format_with(&self, options: &FormatOptions) -> Result<String, CommandError> {
+        format_value(
+            options,
+            vec!["Id", "Title"],
+            vec!["id", "title"],
+            self,
+            None::<fn(&str, &serde_json::Value) -> Cell>,
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct ImportReport {
+    pub value: serde_json::Value,
+}
+
+impl ImportReport {
+    pub fn new(value: serde_json::Value) -> Self {
+        Self { value }
+    }
+
+    pub fn failed(&self) -> usize {
+        rows(&self.value)
+            .filter(|row| row["status"] != "ok")
+            .count()
+    }
+}
+
+impl FormatterValue for ImportReport {
+    fn value(&self) -> &serde_json::Value {
+        &self.value
+    }
+}
+
+impl Formatter for ImportReport {
+    fn format_with(&self, options: &FormatOptions) -> Result<String, CommandError> {
+        format_value(
+            options,
+            vec!["Row", "Pin", "Name", "Status", "Screen Id", "Error"],
+            vec!["row", "pin", "name", "status", "screen_id", "error"],
+            self,
+            None::<fn(&str, &serde_json::Value) -> Cell>,
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct ScreenPlaylists {
+    pub value: serde_json::Value,
+}
+
+impl ScreenPlaylists {
+    pub fn new(value: serde_json::Value) -> Self {
+        Self { value }
+    }
+}
+
+impl FormatterValue for ScreenPlaylists {
+    fn value(&self) -> &serde_json::Value {
+        &self.value
+    }
+}
+
+impl Formatter for ScreenPlaylists {
+    fn format_with(&self, options: &FormatOptions) -> Result<String, CommandError> {
+        format_value(
+            options,
+            vec![
+                "Id",
+                "Title",
+                "Labels",
+                "Enabled",
+                "Priority",
+                "Predicate",
+                "Playing",
+            ],
+            vec![
+                "id",
+                "title",
+                "labels",
+                "is_enabled",
+                "priority",
+                "predicate",
+                "playing",
+            ],
+            self,
+            Some(|field: &str, value: &serde_json::Value| {
+                if field.eq("is_enabled") || field.eq("priority") || field.eq("playing") {
+                    format_boolean_field(value)
+                } else {
+                    Cell::new(value.as_str().unwrap_or("N/A"))
+                }
+            }),
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct OnAirIntervals {
+    pub value: serde_json::Value,
+    /// Title of the calendar events, the playlist title or the simulated predicate.
+    pub summary: String,
+}
+
+impl OnAirIntervals {
+    pub fn new(value: serde_json::Value, summary: String) -> Self {
+        Self { value, summary }
+    }
+
+    /// Renders the intervals as an iCalendar feed with one event per interval.
+    pub fn ical(&self) -> String {
+        let utc = |value: &serde_json::Value| {
+            chrono::DateTime::parse_from_rfc3339(value.as_str().unwrap_or_default())
+                .map(|at| at.to_utc().format("%Y%m%dT%H%M%SZ").to_string())
+                .unwrap_or_default()
+        };
+        let summary = self
+            .summary
+            .replace('\\', "\\\\")
+            .replace(';', "\\;")
+            .replace(',', "\\,")
+            .replace('\n', "\\n");
+        let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ");
+
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//Screenly//screenly-cli//EN".to_string(),
+        ];
+        for (index, interval) in rows(&self.value).enumerate() {
+            let start = utc(&interval["start"]);
+            lines.extend([
+                "BEGIN:VEVENT".to_string(),
+                format!("UID:{start}-{index}@screenly-cli"),
+                format!("DTSTAMP:{stamp}"),
+                format!("DTSTART:{start}"),
+                format!("DTEND:{}", utc(&interval["end"])),
+                format!("SUMMARY:{summary}"),
+                "END:VEVENT".to_string(),
+            ]);
+        }
+        lines.push("END:VCALENDAR".to_string());
+        lines.iter().map(|line| format!("{line}\r\n")).collect()
+    }
+}
+
+impl FormatterValue for OnAirIntervals {
+    fn value(&self) -> &serde_json::Value {
+        &self.value
+    }
+}
+
+impl Formatter for OnAirIntervals {
+    fn format_with(&self, options: &FormatOptions) -> Result<String, CommandError> {
+        format_value(
+            options,
+            vec!["Start", "End", "Duration"],
+            vec!["start", "end", "duration"],
+            self,
+            Some(|field: &str, value: &serde_json::Value| {
+                if field.eq("duration") {
+                    cell!(indicatif::HumanDuration(Duration::from_secs(
+                        value.as_u64().unwrap_or(0)
+                    ))
+                    .to_string())
+                } else {
+                    Cell::new(value.as_str().unwrap_or("N/A"))
+                }
+            }),
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct ScreenStatuses {
+    pub value: serde_json::Value,
+}
+
+impl ScreenStatuses {
+    pub fn new(value: serde_json::Value) -> Self {
+        Self { value }
+    }
+
+    /// Counts the offline screens among the rows `options` filters select.
+    pub fn offline(&self, options: &FormatOptions) -> usize {
+        rows(&options.apply(&self.value))
+            .filter(|row| row["status"] != "online")
+            .count()
+    }
+}
+
+impl FormatterValue for ScreenStatuses {
+    fn value(&self) -> &serde_json::Value {
+        &self.value
+    }
+}
+
+impl Formatter for ScreenStatuses {
+    fn format_with(&self, options: &FormatOptions) -> Result<String, CommandError> {
+        format_value(
+            options,
+            vec![
+                "Id",
+                "Name",
+                "Status",
+                "Last Ping",
+                "Software Version",
+                "Uptime",
+                "Playlist",
+            ],
+            vec![
+                "id",
+                "name",
+                "status",
+                "last_ping",
+                "software_version",
+                "uptime",
+                "playlist",
+            ],
+            self,
+            Some(|field: &str, value: &serde_json::Value| {
+                if field.eq("status") {
+                    if value == "online" {
+                        cell!(c -> "🟢 online")
+                    } else {
+                        cell!(c -> "🔴 offline")
+                    }
+                } else if field.eq("uptime") {
+                    // Players report uptime either as seconds or as a decimal string.
+                    let seconds = value
+                        .as_f64()
+                        .or_else(|| value.as_str().and_then(|uptime| uptime.parse().ok()));
+                    let uptime = match seconds {
+                        Some(seconds) if seconds >= 0.0 => {
+                            indicatif::HumanDuration(Duration::from_secs(seconds as u64))
+                                .to_string()
+                        }
+                        _ => "N/A".to_owned(),
+                    };
+                    Cell::new(&uptime).style_spec("r")
+                } else {
+                    Cell::new(value.as_str().unwrap_or("N/A"))
+                }
+            }),
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct Labels {
+    pub value: serde_json::Value,
+}
+
+impl Labels {
+    pub fn new(value: serde_json::Value) -> Self {
+        Self { value }
+    }
+}
+
+impl FormatterValue for Labels {
+    fn value(&self) -> &serde_json::Value {
+        &self.value
+    }
+}
+
+impl Formatter for Labels {
+    fn format_with(&self, options: &FormatOptions) -> Result<String, CommandError> {
+        format_value(
+            options,
+            vec!["Id", "Name"],
+            vec!["id", "name"],
+            self,
+            None::<fn(&str, &serde_json::Value) -> Cell>,
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct SharedPlaylists {
+    pub value: serde_json::Value,
+}
+
+impl SharedPlaylists {
+    pub fn new(value: serde_json::Value) -> Self {
+        Self { value }
+    }
+}
+
+impl FormatterValue for SharedPlaylists {
+    fn value(&self) -> &serde_json::Value {
+        &self.value
+    }
+}
+
+impl Formatter for SharedPlaylists {
+    fn format_with(&self, options: &FormatOptions) -> Result<String, CommandError> {
+        format_value(
+            options,
+            vec!["Playlist Id", "Team Id"],
+            vec!["playlist_id", "team_id"],
+            self,
+            None::<fn(&str, &serde_json::Value) -> Cell>,
+        )
+    }
+}
+
 #[derive(Debug)]
 pub struct PlaylistItems {
     pub value: serde_json::Value,
@@ -538,9 +1311,9 @@ impl FormatterValue for PlaylistItems {
 }
 
 impl Formatter for PlaylistItems {
-    fn format(&self, output_type: OutputType) -> String {
+    fn format_with(&self, options: &FormatOptions) -> Result<String, CommandError> {
         format_value(
-            output_type,
+            options,
             vec!["Asset Id", "Duration"],
             vec!["asset_id", "duration"],
             self,
@@ -558,9 +1331,183 @@ impl Formatter for PlaylistItems {
     }
 }
 
+#[derive(Debug)]
+pub struct Profiles {
+    pub value: serde_json::Value,
+}
+
+impl Profiles {
+    pub fn new(value: serde_json::Value) -> Self {
+        Self { value }
+    }
+}
+
+impl FormatterValue for Profiles {
+    fn value(&self) -> &serde_json::Value {
+        &self.value
+    }
+}
+
+impl Formatter for Profiles {
+    fn format_with(&self, options: &FormatOptions) -> Result<String, CommandError> {
+        format_value(
+            options,
+            vec!["Name", "URL", "Active", "Logged In"],
+            vec!["name", "url", "active", "logged_in"],
+            self,
+            Some(|field: &str, value: &serde_json::Value| {
+                if field.eq("active") || field.eq("logged_in") {
+                    format_boolean_field(value)
+                } else {
+                    Cell::new(value.as_str().unwrap_or("N/A"))
+                }
+            }),
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct Whoami {
+    pub value: serde_json::Value,
+}
+
+impl Whoami {
+    pub fn new(value: serde_json::Value) -> Self {
+        Self { value }
+    }
+}
+
+impl FormatterValue for Whoami {
+    fn value(&self) -> &serde_json::Value {
+        &self.value
+    }
+}
+
+impl Formatter for Whoami {
+    fn format_with(&self, options: &FormatOptions) -> Result<String, CommandError> {
+        format_value(
+            options,
+            vec![
+                "Profile",
+                "Environment",
+                "API URL",
+                "Account",
+                "Team",
+                "Token Scope",
+                "Expires At",
+                "Status",
+            ],
+            vec![
+                "profile",
+                "environment",
+                "api_url",
+                "account",
+                "team",
+                "scope",
+                "expires_at",
+                "status",
+            ],
+            self,
+            None::<fn(&str, &serde_json::Value) -> Cell>,
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use httpmock::Method::{GET, PATCH};
+    use httpmock::MockServer;
+
     use super::*;
+    use crate::authentication::Config;
+
+    #[test]
+    fn test_get_when_request_rejected_should_return_api_error_with_postgrest_fields() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method(GET).path("/v4/screens");
+            then.status(400).json_body(serde_json::json!({
+                "code": "22P02",
+                "details": null,
+                "hint": "Check the id filter.",
+                "message": "invalid input syntax for type uuid: \"abc\""
+            }));
+        });
+
+        let authentication =
+            Authentication::new_with_config(Config::new(mock_server.base_url()), "token");
+        let e = get(&authentication, "v4/screens?id=eq.abc").unwrap_err();
+
+        let CommandError::Api(api_error) = e else {
+            panic!("unexpected error: {e:?}");
+        };
+        assert_eq!(
+            *api_error,
+            ApiError {
+                status: 400,
+                method: "GET".to_string(),
+                url: format!("{}/v4/screens?id=eq.abc", mock_server.base_url()),
+                message: Some("invalid input syntax for type uuid: \"abc\"".to_string()),
+                details: None,
+                hint: Some("Check the id filter.".to_string()),
+                code: Some("22P02".to_string()),
+            }
+        );
+        assert_eq!(
+            api_error.to_string(),
+            format!(
+                "GET {}/v4/screens?id=eq.abc failed with status 400: invalid input syntax for type uuid: \"abc\"\nHint: Check the id filter.\nCode: 22P02",
+                mock_server.base_url()
+            )
+        );
+    }
+
+    #[test]
+    fn test_mutating_helpers_in_dry_run_should_not_send_requests() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.path("/v4/playlists");
+            then.status(200);
+        });
+
+        let mut config = Config::new(mock_server.base_url());
+        config.dry_run = true;
+        let authentication = Authentication::new_with_config(config, "token");
+        let payload = serde_json::json!({ "title": "Lobby" });
+
+        assert_eq!(
+            post(&authentication, "v4/playlists", &payload).unwrap(),
+            serde_json::json!([{ "title": "Lobby" }])
+        );
+        assert_eq!(
+            patch(&authentication, "v4/playlists?id=eq.1", &payload).unwrap(),
+            serde_json::json!([{ "title": "Lobby" }])
+        );
+        delete(&authentication, "v4/playlists?id=eq.1").unwrap();
+        mock.assert_calls(0);
+    }
+
+    #[test]
+    fn test_patch_when_body_is_not_json_should_use_body_as_message() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method(PATCH).path("/v4/assets");
+            then.status(403).body("Forbidden");
+        });
+
+        let authentication =
+            Authentication::new_with_config(Config::new(mock_server.base_url()), "token");
+        let e = patch(&authentication, "v4/assets", &serde_json::json!({})).unwrap_err();
+
+        assert_eq!(e.status(), Some(403));
+        assert_eq!(
+            e.to_string(),
+            format!(
+                "PATCH {}/v4/assets failed with status 403: Forbidden",
+                mock_server.base_url()
+            )
+        );
+    }
 
     #[test]
     fn test_edge_app_instance_formatter_format_output_properly() {
@@ -574,7 +1521,9 @@ mod tests {
         }]"#;
         let edge_app_instances = EdgeAppInstances::new(serde_json::from_str(data).unwrap());
 
-        let output = edge_app_instances.format(OutputType::HumanReadable);
+        let output = edge_app_instances
+            .format_with(&FormatOptions::new(OutputType::HumanReadable))
+            .unwrap();
         assert_eq!(
             output,
             r#"+----------------------------+------------+
@@ -587,4 +1536,224 @@ mod tests {
 "#
         );
     }
+
+    fn edge_app_instances() -> EdgeAppInstances {
+        EdgeAppInstances::new(serde_json::json!([
+            { "id": "01J1SNE1GMGG8R0ZXZ183ZGN6T", "name": "Lobby, \"North\"" },
+            { "id": "01J1SNE1GMGG8R0ZXZ183ZGN7T", "name": "Test App 2" }
+        ]))
+    }
+
+    #[test]
+    fn test_format_value_with_csv_output_should_quote_fields() {
+        assert_eq!(
+            edge_app_instances().format_with(&FormatOptions::new(OutputType::Csv)).unwrap(),
+            "Id,Name\n01J1SNE1GMGG8R0ZXZ183ZGN6T,\"Lobby, \"\"North\"\"\"\n01J1SNE1GMGG8R0ZXZ183ZGN7T,Test App 2"
+        );
+    }
+
+    #[test]
+    fn test_format_value_with_ndjson_output_should_print_one_row_per_line() {
+        assert_eq!(
+            edge_app_instances()
+                .format_with(&FormatOptions::new(OutputType::Ndjson))
+                .unwrap(),
+            r#"{"id":"01J1SNE1GMGG8R0ZXZ183ZGN6T","name":"Lobby, \"North\""}
+{"id":"01J1SNE1GMGG8R0ZXZ183ZGN7T","name":"Test App 2"}"#
+        );
+    }
+
+    #[test]
+    fn test_format_value_with_yaml_output_should_serialize_value() {
+        assert_eq!(
+            edge_app_instances().format_with(&FormatOptions::new(OutputType::Yaml)).unwrap(),
+            "- id: 01J1SNE1GMGG8R0ZXZ183ZGN6T\n  name: Lobby, \"North\"\n- id: 01J1SNE1GMGG8R0ZXZ183ZGN7T\n  name: Test App 2\n"
+        );
+    }
+
+    #[test]
+    fn test_format_value_with_ids_output_should_print_ids() {
+        assert_eq!(
+            edge_app_instances()
+                .format_with(&FormatOptions::new(OutputType::Ids))
+                .unwrap(),
+            "01J1SNE1GMGG8R0ZXZ183ZGN6T\n01J1SNE1GMGG8R0ZXZ183ZGN7T"
+        );
+
+        let profiles = Profiles::new(serde_json::json!([{ "name": "default", "active": true }]));
+        assert_eq!(
+            profiles
+                .format_with(&FormatOptions::new(OutputType::Ids))
+                .unwrap(),
+            "default"
+        );
+    }
+
+    fn screens() -> Screens {
+        Screens::new(serde_json::json!([
+            { "id": "1", "name": "Lobby", "status": "Offline", "last_ping": "2024-05-01T10:00:00Z", "uptime": 30 },
+            { "id": "2", "name": "Kitchen", "status": "Online", "last_ping": null, "uptime": 120 },
+            { "id": "3", "name": "Garage", "status": "offline", "last_ping": "2024-04-01T10:00:00Z", "uptime": 5 }
+        ]))
+    }
+
+    #[test]
+    fn test_format_with_filter_and_columns_should_select_rows_and_fields() {
+        let options = FormatOptions {
+            output_type: OutputType::Csv,
+            columns: Some(vec![
+                "name".to_string(),
+                "Last Ping".to_string(),
+                "status".to_string(),
+            ]),
+            filters: vec!["status=offline".parse().unwrap()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            screens().format_with(&options).unwrap(),
+            "Name,Last Ping,status\nLobby,2024-05-01T10:00:00Z,Offline\nGarage,2024-04-01T10:00:00Z,offline"
+        );
+    }
+
+    #[test]
+    fn test_format_options_streams_should_require_line_per_row_output() {
+        assert!(FormatOptions::new(OutputType::Ndjson).streams());
+        assert!(FormatOptions::new(OutputType::Ids).streams());
+        assert!(!FormatOptions::new(OutputType::Json).streams());
+        assert!(!FormatOptions::new(OutputType::HumanReadable).streams());
+        assert!(!FormatOptions {
+            sort_by: Some("name".to_string()),
+            ..FormatOptions::new(OutputType::Ids)
+        }
+        .streams());
+    }
+
+    #[test]
+    fn test_format_with_regex_filter_and_descending_sort_should_order_rows() {
+        let options = FormatOptions {
+            output_type: OutputType::Ids,
+            sort_by: Some("-uptime".to_string()),
+            filters: vec!["name~^(Lobby|Kitchen)$".parse().unwrap()],
+            ..Default::default()
+        };
+        assert_eq!(screens().format_with(&options).unwrap(), "2\n1");
+
+        let options = FormatOptions {
+            output_type: OutputType::Ids,
+            sort_by: Some("last_ping".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(screens().format_with(&options).unwrap(), "3\n1\n2");
+    }
+
+    #[test]
+    fn test_format_with_columns_should_project_json_rows() {
+        let options = FormatOptions {
+            output_type: OutputType::Ndjson,
+            columns: Some(vec!["id".to_string(), "status".to_string()]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            screens().format_with(&options).unwrap(),
+            "{\"id\":\"1\",\"status\":\"Offline\"}\n{\"id\":\"2\",\"status\":\"Online\"}\n{\"id\":\"3\",\"status\":\"offline\"}"
+        );
+    }
+
+    #[test]
+    fn test_row_filter_from_str_should_validate_expression() {
+        assert_eq!(
+            "status=offline".parse::<RowFilter>().unwrap(),
+            RowFilter {
+                field: "status".to_string(),
+                pattern: "offline".to_string(),
+                regex: false,
+            }
+        );
+        assert_eq!(
+            "name~a=b".parse::<RowFilter>().unwrap(),
+            RowFilter {
+                field: "name".to_string(),
+                pattern: "a=b".to_string(),
+                regex: true,
+            }
+        );
+        assert!("status".parse::<RowFilter>().is_err());
+        assert!("=offline".parse::<RowFilter>().is_err());
+        assert!("name~(".parse::<RowFilter>().is_err());
+    }
+
+    #[test]
+    fn test_format_with_item_template_should_render_every_row() {
+        let options = FormatOptions {
+            filters: vec!["status=offline".parse().unwrap()],
+            template: Some(
+                OutputTemplate::new(
+                    "{{ index }}. {{ name }} ({{ item.status }})",
+                    TemplateScope::Item,
+                )
+                .unwrap(),
+            ),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            screens().format_with(&options).unwrap(),
+            "0. Lobby (Offline)\n1. Garage (offline)"
+        );
+    }
+
+    #[test]
+    fn test_format_with_collection_template_from_file_should_render_once() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("report.j2");
+        std::fs::write(
+            &path,
+            "{{ items | length }} screens: {% for s in items %}{{ s.name }}{% if not loop.last %}, {% endif %}{% endfor %}",
+        )
+        .unwrap();
+
+        let template = OutputTemplate::new(
+            &format!("@{}", path.to_str().unwrap()),
+            TemplateScope::Collection,
+        )
+        .unwrap();
+        let options = FormatOptions {
+            template: Some(template),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            screens().format_with(&options).unwrap(),
+            "3 screens: Lobby, Kitchen, Garage"
+        );
+    }
+
+    #[test]
+    fn test_format_with_template_render_error_should_return_error() {
+        let options = FormatOptions {
+            template: Some(
+                OutputTemplate::new("{{ name.first.letter }}", TemplateScope::Item).unwrap(),
+            ),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            screens().format_with(&options),
+            Err(CommandError::Template(_))
+        ));
+    }
+
+    #[test]
+    fn test_output_template_new_should_reject_invalid_templates() {
+        assert!(matches!(
+            OutputTemplate::new("{{ name", TemplateScope::Item),
+            Err(CommandError::Template(_))
+        ));
+        assert!(matches!(
+            OutputTemplate::new("@/nonexistent/template.j2", TemplateScope::Item),
+            Err(CommandError::Io(_))
+        ));
+    }
 }
@@ -0,0 +1,319 @@
+use reqwest::header::{HeaderMap, CONTENT_RANGE, RANGE};
+use reqwest::{Method, StatusCode};
+
+use crate::authentication::Authentication;
+use crate::commands::retry::send_with_retry;
+use crate::commands::{ApiError, CommandError};
+
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// How many rows a list request fetches in total and per request.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pagination {
+    pub limit: Option<usize>,
+    pub page_size: usize,
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Self {
+            limit: None,
+            page_size: DEFAULT_PAGE_SIZE,
+        }
+    }
+}
+
+/// Walks a v4 collection page by page using PostgREST `Range` headers.
+/// Every item is one page of rows, so callers can process rows before the
+/// whole collection has been fetched. `page_size` must not be 0.
+pub struct Pages<'a> {
+    authentication: &'a Authentication,
+    url: String,
+    pagination: Pagination,
+    offset: usize,
+    done: bool,
+}
+
+impl<'a> Pages<'a> {
+    pub fn new(authentication: &'a Authentication, endpoint: &str, pagination: Pagination) -> Self {
+        Self {
+            authentication,
+            url: format!("{}/{}", &authentication.config.url, endpoint),
+            pagination,
+            offset: 0,
+            done: false,
+        }
+    }
+
+    /// The most rows the pages add up to, if limited. A page may hold more rows than asked for.
+    pub fn limit(&self) -> Option<usize> {
+        self.pagination.limit
+    }
+
+    /// Fetches the remaining pages, up to the limit, into one JSON array.
+    pub fn rows(self) -> Result<serde_json::Value, CommandError> {
+        let limit = self.limit();
+        let mut rows = Vec::new();
+        for page in self {
+            rows.extend(page?);
+        }
+        if let Some(limit) = limit {
+            rows.truncate(limit);
+        }
+        Ok(serde_json::Value::Array(rows))
+    }
+
+    fn fetch(&mut self, count: usize) -> Result<Vec<serde_json::Value>, CommandError> {
+        let mut headers = HeaderMap::new();
+        headers.insert("Range-Unit", "items".parse()?);
+        // Without a count, PostgREST answers `200` with an unknown total (`0-99/*`).
+        headers.insert("Prefer", "count=exact".parse()?);
+        headers.insert(
+            RANGE,
+            format!("{}-{}", self.offset, self.offset + count - 1).parse()?,
+        );
+
+        let client = self.authentication.build_client()?;
+        let response = send_with_retry(self.authentication, true, || {
+            Ok(client.get(&self.url).headers(headers.clone()))
+        })?;
+
+        let status = response.status();
+        // The requested range starts past the end of the collection.
+        if status == StatusCode::RANGE_NOT_SATISFIABLE {
+            self.done = true;
+            return Ok(Vec::new());
+        }
+        if status != StatusCode::OK && status != StatusCode::PARTIAL_CONTENT {
+            return Err(ApiError::from_response(Method::GET, response).into());
+        }
+
+        let content_range = response
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let total = content_range.as_deref().and_then(content_range_total);
+        let rows = match serde_json::from_str(&response.text()?)? {
+            serde_json::Value::Array(rows) => rows,
+            value => vec![value],
+        };
+
+        self.offset += rows.len();
+        // A response without `Content-Range` means the server ignored `Range` and returned
+        // the whole collection at once, so asking for the next range would only repeat it.
+        self.done = content_range.is_none()
+            || rows.len() < count
+            || total.is_some_and(|total| self.offset >= total);
+        Ok(rows)
+    }
+}
+
+impl Iterator for Pages<'_> {
+    type Item = Result<Vec<serde_json::Value>, CommandError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let remaining = self
+            .pagination
+            .limit
+            .map(|limit| limit.saturating_sub(self.offset));
+        let count = match remaining {
+            Some(0) => return None,
+            Some(remaining) => remaining.min(self.pagination.page_size),
+            None => self.pagination.page_size,
+        };
+
+        let page = self.fetch(count);
+        if page.is_err() {
+            self.done = true;
+        }
+        Some(page)
+    }
+}
+
+/// Fetches every page of `endpoint`, up to `pagination.limit` rows, into one JSON array.
+/// Use [`Pages`] instead to handle rows as they arrive.
+pub fn get_all(
+    authentication: &Authentication,
+    endpoint: &str,
+    pagination: Pagination,
+) -> Result<serde_json::Value, CommandError> {
+    Pages::new(authentication, endpoint, pagination).rows()
+}
+
+// Parses the total out of `Content-Range: 0-99/1234`. The total is `*` when unknown.
+fn content_range_total(value: &str) -> Option<usize> {
+    value.split_once('/')?.1.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use httpmock::Method::GET;
+    use httpmock::MockServer;
+    use serde_json::json;
+
+    use super::*;
+    use crate::authentication::Config;
+
+    fn rows(from: usize, to: usize) -> serde_json::Value {
+        json!((from..to).map(|i| json!({ "id": i })).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_get_all_should_follow_content_range_until_collection_is_exhausted() {
+        let mock_server = MockServer::start();
+        let first = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/screens")
+                .header("Range-Unit", "items")
+                .header("Prefer", "count=exact")
+                .header("Range", "0-1");
+            then.status(206)
+                .header("Content-Range", "0-1/5")
+                .json_body(rows(0, 2));
+        });
+        let second = mock_server.mock(|when, then| {
+            when.method(GET).path("/v4/screens").header("Range", "2-3");
+            then.status(206)
+                .header("Content-Range", "2-3/5")
+                .json_body(rows(2, 4));
+        });
+        let third = mock_server.mock(|when, then| {
+            when.method(GET).path("/v4/screens").header("Range", "4-5");
+            then.status(206)
+                .header("Content-Range", "4-4/5")
+                .json_body(rows(4, 5));
+        });
+
+        let authentication =
+            Authentication::new_with_config(Config::new(mock_server.base_url()), "token");
+        let pagination = Pagination {
+            limit: None,
+            page_size: 2,
+        };
+        let result = get_all(&authentication, "v4/screens", pagination).unwrap();
+
+        assert_eq!(result, rows(0, 5));
+        first.assert();
+        second.assert();
+        third.assert();
+    }
+
+    #[test]
+    fn test_get_all_should_stop_at_limit() {
+        let mock_server = MockServer::start();
+        let first = mock_server.mock(|when, then| {
+            when.method(GET).path("/v4/assets").header("Range", "0-1");
+            then.status(206)
+                .header("Content-Range", "0-1/10")
+                .json_body(rows(0, 2));
+        });
+        let second = mock_server.mock(|when, then| {
+            when.method(GET).path("/v4/assets").header("Range", "2-2");
+            then.status(206)
+                .header("Content-Range", "2-2/10")
+                .json_body(rows(2, 3));
+        });
+
+        let authentication =
+            Authentication::new_with_config(Config::new(mock_server.base_url()), "token");
+        let pagination = Pagination {
+            limit: Some(3),
+            page_size: 2,
+        };
+        let result = get_all(&authentication, "v4/assets", pagination).unwrap();
+
+        assert_eq!(result, rows(0, 3));
+        first.assert();
+        second.assert();
+    }
+
+    #[test]
+    fn test_get_all_when_range_not_satisfiable_should_end_collection() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/playlists")
+                .header("Range", "0-1");
+            then.status(200)
+                .header("Content-Range", "0-1/*")
+                .json_body(rows(0, 2));
+        });
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/playlists")
+                .header("Range", "2-3");
+            then.status(416).header("Content-Range", "*/2");
+        });
+
+        let authentication =
+            Authentication::new_with_config(Config::new(mock_server.base_url()), "token");
+        let pagination = Pagination {
+            limit: None,
+            page_size: 2,
+        };
+        let result = get_all(&authentication, "v4/playlists", pagination).unwrap();
+
+        assert_eq!(result, rows(0, 2));
+    }
+
+    #[test]
+    fn test_get_all_when_total_is_unknown_should_fetch_next_range() {
+        let mock_server = MockServer::start();
+        let first = mock_server.mock(|when, then| {
+            when.method(GET).path("/v4/labels").header("Range", "0-1");
+            then.status(200)
+                .header("Content-Range", "0-1/*")
+                .json_body(rows(0, 2));
+        });
+        let second = mock_server.mock(|when, then| {
+            when.method(GET).path("/v4/labels").header("Range", "2-3");
+            then.status(200)
+                .header("Content-Range", "2-2/*")
+                .json_body(rows(2, 3));
+        });
+
+        let authentication =
+            Authentication::new_with_config(Config::new(mock_server.base_url()), "token");
+        let pagination = Pagination {
+            limit: None,
+            page_size: 2,
+        };
+        let result = get_all(&authentication, "v4/labels", pagination).unwrap();
+
+        assert_eq!(result, rows(0, 3));
+        first.assert();
+        second.assert();
+    }
+
+    #[test]
+    fn test_get_all_when_server_ignores_range_should_fetch_once() {
+        let mock_server = MockServer::start();
+        let full = mock_server.mock(|when, then| {
+            when.method(GET).path("/v4/labels");
+            then.status(200).json_body(rows(0, 3));
+        });
+
+        let authentication =
+            Authentication::new_with_config(Config::new(mock_server.base_url()), "token");
+        let pagination = Pagination {
+            limit: None,
+            page_size: 2,
+        };
+        let result = get_all(&authentication, "v4/labels", pagination).unwrap();
+
+        assert_eq!(result, rows(0, 3));
+        full.assert_calls(1);
+    }
+
+    #[test]
+    fn test_content_range_total_should_handle_unknown_totals() {
+        assert_eq!(content_range_total("0-99/1234"), Some(1234));
+        assert_eq!(content_range_total("0-99/*"), None);
+        assert_eq!(content_range_total("*/0"), Some(0));
+    }
+}
@@ -1,8 +1,19 @@
+use chrono::NaiveDate;
+use log::warn;
 use serde_json::json;
 
 use crate::authentication::Authentication;
 use crate::commands;
-use crate::commands::{CommandError, PlaylistFile, PlaylistItem, PlaylistItems, Playlists};
+use crate::commands::label;
+use crate::commands::lookup::{self, Resource};
+use crate::commands::pagination::{self, Pages, Pagination};
+use crate::commands::predicate;
+use crate::commands::predicate_builder;
+use crate::commands::schedule::{self, Timezone};
+use crate::commands::{
+    CommandError, OnAirIntervals, PlaylistFile, PlaylistItem, PlaylistItems, Playlists,
+    SharedPlaylists,
+};
 
 const POSITION_MULTIPLIER: u64 = 100000;
 pub struct PlaylistCommand {
@@ -14,14 +25,42 @@ impl PlaylistCommand {
         Self { authentication }
     }
 
-    pub fn list(&self) -> Result<Playlists, CommandError> {
-        Ok(Playlists::new(commands::get(
-            &self.authentication,
-            "v4/playlists",
-        )?))
+    /// Resolves a playlist id, title or `name:<glob>` selector to an id.
+    pub fn resolve(&self, reference: &str) -> Result<String, CommandError> {
+        lookup::resolve(&self.authentication, Resource::Playlist, reference)
+    }
+
+    /// Resolves an asset id, title or `name:<glob>` selector to an id.
+    pub fn resolve_asset(&self, reference: &str) -> Result<String, CommandError> {
+        lookup::resolve(&self.authentication, Resource::Asset, reference)
+    }
+
+    /// Resolves a team name or `name:<glob>` selector to an id. Ids are used as they are,
+    /// since `v4/teams` only lists the caller's own teams.
+    pub fn resolve_team(&self, reference: &str) -> Result<String, CommandError> {
+        if lookup::is_id(reference) {
+            return Ok(reference.to_string());
+        }
+        lookup::resolve(&self.authentication, Resource::Team, reference)
+    }
+
+    /// Resolves a screen id, name or `name:<glob>` selector to an id.
+    pub fn resolve_screen(&self, reference: &str) -> Result<String, CommandError> {
+        lookup::resolve(&self.authentication, Resource::Screen, reference)
+    }
+
+    /// Resolves a label id, name or `name:<glob>` selector to an id.
+    pub fn resolve_label(&self, reference: &str) -> Result<String, CommandError> {
+        lookup::resolve(&self.authentication, Resource::Label, reference)
+    }
+
+    /// The rows of the list command, one page at a time.
+    pub fn pages(&self, pagination: Pagination) -> Pages<'_> {
+        Pages::new(&self.authentication, "v4/playlists", pagination)
     }
 
     pub fn create(&self, title: &str, predicate: &str) -> Result<Playlists, CommandError> {
+        predicate::parse(predicate)?;
         let response = commands::post(
             &self.authentication,
             "v4/playlists",
@@ -65,6 +104,7 @@ impl PlaylistCommand {
     pub fn update(&self, playlist: &PlaylistFile) -> Result<PlaylistItems, CommandError> {
         let old_predicate = self.get_playlist_field(&playlist.playlist_id, "predicate")?;
         if old_predicate != playlist.predicate {
+            predicate::parse(&playlist.predicate)?;
             commands::patch(
                 &self.authentication,
                 &format!("v4/playlists?id=eq.{id}", id = playlist.playlist_id),
@@ -163,6 +203,185 @@ impl PlaylistCommand {
 
         self.update(&playlist_file)
     }
+
+    /// Shares a playlist with another team.
+    pub fn share(&self, playlist_id: &str, team_id: &str) -> Result<SharedPlaylists, CommandError> {
+        Ok(SharedPlaylists::new(commands::post(
+            &self.authentication,
+            "v4/playlists/shared",
+            &json!({"playlist_id": playlist_id, "team_id": team_id}),
+        )?))
+    }
+
+    pub fn unshare(&self, playlist_id: &str, team_id: &str) -> Result<(), CommandError> {
+        commands::delete(
+            &self.authentication,
+            &format!("v4/playlists/shared?playlist_id=eq.{playlist_id}&team_id=eq.{team_id}"),
+        )
+    }
+
+    /// Lists playlist shares, only those of one playlist when `playlist_id` is given.
+    pub fn shares(
+        &self,
+        playlist_id: Option<&str>,
+        pagination: Pagination,
+    ) -> Result<SharedPlaylists, CommandError> {
+        let endpoint = match playlist_id {
+            Some(playlist_id) => format!("v4/playlists/shared?playlist_id=eq.{playlist_id}"),
+            None => "v4/playlists/shared".to_string(),
+        };
+        Ok(SharedPlaylists::new(pagination::get_all(
+            &self.authentication,
+            &endpoint,
+            pagination,
+        )?))
+    }
+
+    /// Replaces the predicate of a playlist, leaving its items alone.
+    pub fn set_predicate(&self, uuid: &str, predicate: &str) -> Result<Playlists, CommandError> {
+        predicate::parse(predicate)?;
+        let response = commands::patch(
+            &self.authentication,
+            &format!("v4/playlists?id=eq.{uuid}"),
+            &json!({"predicate": predicate}),
+        )?;
+        if response
+            .as_array()
+            .is_some_and(|playlists| playlists.is_empty())
+        {
+            return Err(CommandError::ResourceNotFound(
+                "playlist".to_string(),
+                uuid.to_string(),
+            ));
+        }
+        Ok(Playlists::new(response))
+    }
+
+    /// Describes in plain English when the playlist is shown.
+    pub fn explain(&self, uuid: &str) -> Result<String, CommandError> {
+        let playlist = self.scheduling(uuid)?;
+        Ok(predicate_builder::explain(&predicate::parse(
+            playlist["predicate"].as_str().unwrap_or("TRUE"),
+        )?))
+    }
+
+    /// Lists when the playlist's predicate puts it on air from `from` to `to` in `timezone`.
+    pub fn simulate(
+        &self,
+        uuid: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+        timezone: Timezone,
+    ) -> Result<OnAirIntervals, CommandError> {
+        let playlist = self.scheduling(uuid)?;
+        schedule::simulate(
+            playlist["predicate"].as_str().unwrap_or("TRUE"),
+            from,
+            to,
+            timezone,
+            playlist["title"].as_str().unwrap_or(uuid),
+        )
+    }
+
+    // The title, predicate and state of a playlist, warning when it is disabled.
+    fn scheduling(&self, uuid: &str) -> Result<serde_json::Value, CommandError> {
+        let playlists = commands::get(
+            &self.authentication,
+            &format!("v4/playlists?id=eq.{uuid}&select=title,predicate,is_enabled"),
+        )?;
+        let Some(playlist) = playlists.as_array().and_then(|playlists| playlists.first()) else {
+            return Err(CommandError::ResourceNotFound(
+                "playlist".to_string(),
+                uuid.to_string(),
+            ));
+        };
+        if !playlist["is_enabled"].as_bool().unwrap_or(false) {
+            warn!(
+                "Playlist {} is disabled, it is never shown whatever its predicate says.",
+                playlist["title"].as_str().unwrap_or(uuid)
+            );
+        }
+        Ok(playlist.clone())
+    }
+
+    pub fn assign_label(&self, playlist_id: &str, label_id: &str) -> Result<(), CommandError> {
+        assign_label(&self.authentication, playlist_id, label_id)
+    }
+
+    pub fn assign_screen(&self, playlist_id: &str, screen_id: &str) -> Result<(), CommandError> {
+        assign_screen(&self.authentication, playlist_id, screen_id)
+    }
+
+    pub fn unassign_label(&self, playlist_id: &str, label_id: &str) -> Result<(), CommandError> {
+        commands::delete(
+            &self.authentication,
+            &format!("v4/labels/playlists?label_id=eq.{label_id}&playlist_id=eq.{playlist_id}"),
+        )
+    }
+
+    /// Removes a playlist assigned with [`assign_screen`]. The playlist keeps playing
+    /// on the screen if it also reaches it through another label.
+    pub fn unassign_screen(&self, playlist_id: &str, screen_id: &str) -> Result<(), CommandError> {
+        match screen_label(&self.authentication, screen_id)? {
+            Some(label_id) => self.unassign_label(playlist_id, &label_id),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Shows the playlist on the screens that carry a label. An existing link is left alone.
+pub fn assign_label(
+    authentication: &Authentication,
+    playlist_id: &str,
+    label_id: &str,
+) -> Result<(), CommandError> {
+    let linked = label::playlist_label_ids(authentication, playlist_id)?;
+    if !linked.iter().any(|id| id == label_id) {
+        label::link_playlist(authentication, label_id, playlist_id)?;
+    }
+    Ok(())
+}
+
+/// Shows the playlist on a single screen.
+///
+/// Playlists only reach screens through labels, so the playlist is linked to a label of
+/// the screen's own, named `screen:<screen id>`, which is created and put on the screen
+/// when needed.
+pub fn assign_screen(
+    authentication: &Authentication,
+    playlist_id: &str,
+    screen_id: &str,
+) -> Result<(), CommandError> {
+    let label_id = match screen_label(authentication, screen_id)? {
+        Some(label_id) => label_id,
+        None => {
+            let created = label::create(authentication, &screen_label_name(screen_id))?;
+            commands::created_id(authentication, &created.value)?
+        }
+    };
+    let carried = label::screen_label_ids(authentication, screen_id)?;
+    if !carried.contains(&label_id) {
+        label::link_screen(authentication, &label_id, screen_id)?;
+    }
+    assign_label(authentication, playlist_id, &label_id)
+}
+
+fn screen_label(
+    authentication: &Authentication,
+    screen_id: &str,
+) -> Result<Option<String>, CommandError> {
+    let labels = commands::get(
+        authentication,
+        &format!(
+            "v4/labels?select=id&name=eq.{}",
+            screen_label_name(screen_id)
+        ),
+    )?;
+    Ok(labels[0]["id"].as_str().map(str::to_string))
+}
+
+fn screen_label_name(screen_id: &str) -> String {
+    format!("screen:{screen_id}")
 }
 
 #[cfg(test)]
@@ -212,6 +431,120 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_create_playlist_with_invalid_predicate_should_not_send_request() {
+        let mock_server = MockServer::start();
+        let post_mock = mock_server.mock(|when, then| {
+            when.method(POST).path("/v4/playlists");
+            then.status(201).json_body(json!([]));
+        });
+
+        let config = Config::new(mock_server.base_url());
+        let command = PlaylistCommand::new(Authentication::new_with_config(config, "token"));
+        let error = command.create("Best playlist", "$WEEKDAY = 7").unwrap_err();
+
+        assert!(matches!(
+            error,
+            CommandError::InvalidPredicate(ref e) if e.column == 12
+        ));
+        post_mock.assert_calls(0);
+    }
+
+    #[test]
+    fn test_set_predicate_and_explain_should_round_trip() {
+        let mock_server = MockServer::start();
+        let patch_mock = mock_server.mock(|when, then| {
+            when.method(PATCH)
+                .path("/v4/playlists")
+                .query_param("id", "eq.01H3M50TFHSRMEP61BBPWXKRCA")
+                .json_body(json!({"predicate": "$WEEKDAY IN {0, 6}"}));
+            then.status(200).json_body(
+                json!([{"id": "01H3M50TFHSRMEP61BBPWXKRCA", "predicate": "$WEEKDAY IN {0, 6}"}]),
+            );
+        });
+        let get_mock = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/playlists")
+                .query_param("id", "eq.01H3M50TFHSRMEP61BBPWXKRCA")
+                .query_param("select", "title,predicate,is_enabled");
+            then.status(200).json_body(json!([{
+                "title": "Weekend",
+                "predicate": "$WEEKDAY IN {0, 6}",
+                "is_enabled": true
+            }]));
+        });
+
+        let config = Config::new(mock_server.base_url());
+        let command = PlaylistCommand::new(Authentication::new_with_config(config, "token"));
+        assert!(matches!(
+            command.set_predicate("01H3M50TFHSRMEP61BBPWXKRCA", "$WEEKDAY IN {0, 7}"),
+            Err(CommandError::InvalidPredicate(_))
+        ));
+        command
+            .set_predicate("01H3M50TFHSRMEP61BBPWXKRCA", "$WEEKDAY IN {0, 6}")
+            .unwrap();
+        patch_mock.assert_calls(1);
+
+        let explanation = command.explain("01H3M50TFHSRMEP61BBPWXKRCA").unwrap();
+        get_mock.assert();
+        assert_eq!(explanation, "Shown on Saturday and Sunday.");
+    }
+
+    #[test]
+    fn test_set_predicate_when_playlist_does_not_exist_should_return_error() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method(PATCH)
+                .path("/v4/playlists")
+                .query_param("id", "eq.01H3M50TFHSRMEP61BBPWXKRCA");
+            then.status(200).json_body(json!([]));
+        });
+
+        let config = Config::new(mock_server.base_url());
+        let command = PlaylistCommand::new(Authentication::new_with_config(config, "token"));
+        assert!(matches!(
+            command.set_predicate("01H3M50TFHSRMEP61BBPWXKRCA", "TRUE"),
+            Err(CommandError::ResourceNotFound(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_simulate_playlist_should_use_its_predicate_and_title() {
+        let mock_server = MockServer::start();
+        let get_mock = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/playlists")
+                .query_param("id", "eq.01H3M50TFHSRMEP61BBPWXKRCA")
+                .query_param("select", "title,predicate,is_enabled");
+            then.status(200).json_body(json!([{
+                "title": "Weekend",
+                "predicate": "$WEEKDAY IN {0, 6}",
+                "is_enabled": true
+            }]));
+        });
+
+        let config = Config::new(mock_server.base_url());
+        let command = PlaylistCommand::new(Authentication::new_with_config(config, "token"));
+        let intervals = command
+            .simulate(
+                "01H3M50TFHSRMEP61BBPWXKRCA",
+                NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(),
+                NaiveDate::from_ymd_opt(2026, 10, 31).unwrap(),
+                "Asia/Tokyo".parse().unwrap(),
+            )
+            .unwrap();
+
+        get_mock.assert();
+        assert_eq!(intervals.summary, "Weekend");
+        assert_eq!(
+            intervals.value,
+            json!([
+                {"start": "2026-10-24T00:00:00+09:00", "end": "2026-10-26T00:00:00+09:00", "duration": 172800},
+                {"start": "2026-10-31T00:00:00+09:00", "end": "2026-11-01T00:00:00+09:00", "duration": 86400},
+            ])
+        );
+    }
+
     #[test]
     fn test_list_playlists_should_send_correct_request() {
         let _test = set_env(OsString::from("API_TOKEN"), "token");
@@ -230,7 +563,7 @@ mod tests {
         let config = Config::new(mock_server.base_url());
         let authentication = Authentication::new_with_config(config, "token");
         let command = PlaylistCommand::new(authentication);
-        let result = command.list();
+        let result = command.pages(Pagination::default()).rows();
         playlists_mock.assert();
         assert!(result.is_ok());
     }
@@ -579,4 +912,154 @@ mod tests {
         get_items_mock.assert();
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_share_and_unshare_playlist_should_use_shared_endpoint() {
+        let mock_server = MockServer::start();
+        let share_mock = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/v4/playlists/shared")
+                .json_body(json!({"playlist_id": "playlist", "team_id": "team"}));
+            then.status(201)
+                .json_body(json!([{"playlist_id": "playlist", "team_id": "team"}]));
+        });
+        let unshare_mock = mock_server.mock(|when, then| {
+            when.method(DELETE)
+                .path("/v4/playlists/shared")
+                .query_param("playlist_id", "eq.playlist")
+                .query_param("team_id", "eq.team");
+            then.status(204);
+        });
+        let shares_mock = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/playlists/shared")
+                .query_param("playlist_id", "eq.playlist");
+            then.status(200)
+                .json_body(json!([{"playlist_id": "playlist", "team_id": "team"}]));
+        });
+
+        let config = Config::new(mock_server.base_url());
+        let command = PlaylistCommand::new(Authentication::new_with_config(config, "token"));
+        let shared = command.share("playlist", "team").unwrap();
+        assert_eq!(shared.value[0]["team_id"], "team");
+        let shares = command
+            .shares(Some("playlist"), Pagination::default())
+            .unwrap();
+        assert_eq!(shares.value, shared.value);
+        command.unshare("playlist", "team").unwrap();
+
+        share_mock.assert();
+        shares_mock.assert();
+        unshare_mock.assert();
+    }
+
+    #[test]
+    fn test_resolve_team_when_reference_is_id_should_not_look_it_up() {
+        let mock_server = MockServer::start();
+        let teams_mock = mock_server.mock(|when, then| {
+            when.method(GET).path("/v4/teams");
+            then.status(200).json_body(json!([]));
+        });
+
+        let config = Config::new(mock_server.base_url());
+        let command = PlaylistCommand::new(Authentication::new_with_config(config, "token"));
+        let team = "01H2QZ6Z8WXWNDC0KQ198XCZEW";
+        assert_eq!(command.resolve_team(team).unwrap(), team);
+        assert!(command.resolve_team("Acme").is_err());
+
+        teams_mock.assert_calls(1);
+    }
+
+    #[test]
+    fn test_assign_screen_should_create_screen_label_once() {
+        let mock_server = MockServer::start();
+        let mut find_label = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/labels")
+                .query_param("name", "eq.screen:screen");
+            then.status(200).json_body(json!([]));
+        });
+        let create_label = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/v4/labels")
+                .json_body(json!({"name": "screen:screen"}));
+            then.status(201)
+                .json_body(json!([{"id": "label", "name": "screen:screen"}]));
+        });
+        let screen_labels = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/labels/screens")
+                .query_param("screen_id", "eq.screen");
+            then.status(200).json_body(json!([]));
+        });
+        let link_screen = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/v4/labels/screens")
+                .json_body(json!({"label_id": "label", "screen_id": "screen"}));
+            then.status(201).json_body(json!([]));
+        });
+        let playlist_labels = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/labels/playlists")
+                .query_param("playlist_id", "eq.playlist");
+            then.status(200).json_body(json!([]));
+        });
+        let link_playlist = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/v4/labels/playlists")
+                .json_body(json!({"label_id": "label", "playlist_id": "playlist"}));
+            then.status(201).json_body(json!([]));
+        });
+
+        let config = Config::new(mock_server.base_url());
+        let command = PlaylistCommand::new(Authentication::new_with_config(config, "token"));
+        command.assign_screen("playlist", "screen").unwrap();
+
+        find_label.assert();
+        create_label.assert();
+        screen_labels.assert();
+        link_screen.assert();
+        playlist_labels.assert();
+        link_playlist.assert();
+
+        find_label.delete();
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/labels")
+                .query_param("name", "eq.screen:screen");
+            then.status(200).json_body(json!([{"id": "label"}]));
+        });
+        let unlink_playlist = mock_server.mock(|when, then| {
+            when.method(DELETE)
+                .path("/v4/labels/playlists")
+                .query_param("label_id", "eq.label")
+                .query_param("playlist_id", "eq.playlist");
+            then.status(204);
+        });
+        command.unassign_screen("playlist", "screen").unwrap();
+
+        unlink_playlist.assert();
+        create_label.assert_calls(1);
+    }
+
+    #[test]
+    fn test_assign_label_should_leave_existing_link_alone() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/labels/playlists")
+                .query_param("playlist_id", "eq.playlist");
+            then.status(200).json_body(json!([{"label_id": "label"}]));
+        });
+        let link_playlist = mock_server.mock(|when, then| {
+            when.method(POST).path("/v4/labels/playlists");
+            then.status(201).json_body(json!([]));
+        });
+
+        let config = Config::new(mock_server.base_url());
+        let command = PlaylistCommand::new(Authentication::new_with_config(config, "token"));
+        command.assign_label("playlist", "label").unwrap();
+
+        link_playlist.assert_calls(0);
+    }
 }
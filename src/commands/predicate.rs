@@ -0,0 +1,736 @@
+//! Playlist predicates, the expressions that decide when a playlist is shown.
+//!
+//! Predicates compare the context variables `$DATE` (the date as a Unix timestamp in
+//! milliseconds), `$TIME` (milliseconds since midnight) and `$WEEKDAY` (0 is Sunday) with
+//! `=`, `<`, `<=`, `>`, `>=`, `BETWEEN {min, max}` and `IN {a, b, ...}`, and combine the
+//! comparisons with `AND`, `OR`, `NOT` and parentheses:
+//!
+//! ```text
+//! expr       := and ("OR" and)*
+//! and        := not ("AND" not)*
+//! not        := "NOT" not | "TRUE" | "FALSE" | "(" expr ")" | comparison
+//! comparison := operand ("=" | "<" | "<=" | ">" | ">=") operand
+//!             | operand "BETWEEN" "{" operand "," operand "}"
+//!             | operand "IN" "{" operand ("," operand)* "}"
+//! operand    := number | "$DATE" | "$TIME" | "$WEEKDAY"
+//! ```
+//!
+//! Keywords and variables are case-insensitive. [`Expr`] prints in a canonical form that
+//! parses back to the same tree.
+
+use std::fmt;
+
+use chrono::{Datelike, NaiveDateTime, NaiveTime, Timelike};
+
+/// Milliseconds in a day, the upper bound of `$TIME`.
+pub const DAY_MS: i64 = 86_400_000;
+
+// `$DATE` literals below this (1973-03-03) are most likely seconds rather than milliseconds.
+const MIN_DATE_MS: i64 = 100_000_000_000;
+
+/// Parses and validates a predicate.
+pub fn parse(predicate: &str) -> Result<Expr, PredicateError> {
+    let mut parser = Parser {
+        predicate,
+        tokens: tokenize(predicate)?,
+        pos: 0,
+    };
+    let expr = parser.expr()?;
+    match parser.peek() {
+        Some(token) => Err(parser.error(
+            token.column,
+            format!("expected `AND`, `OR` or the end but found `{}`", token.kind),
+        )),
+        None => Ok(expr),
+    }
+}
+
+/// Whether `predicate` holds at the local time `at`.
+pub fn evaluate(predicate: &str, at: NaiveDateTime) -> Result<bool, PredicateError> {
+    Ok(parse(predicate)?.evaluate(at))
+}
+
+/// A predicate that failed to parse or validate, pointing at the column of the problem.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PredicateError {
+    pub predicate: String,
+    /// 1-based column, counted in characters.
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for PredicateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} at column {}:", self.message, self.column)?;
+        writeln!(f, "  {}", self.predicate)?;
+        write!(f, "  {:>width$}", "^", width = self.column)
+    }
+}
+
+impl std::error::Error for PredicateError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr {
+    Bool(bool),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(Operand, Comparison, Operand),
+    /// Holds when the value lies within the bounds, both included.
+    Between(Operand, Operand, Operand),
+    In(Operand, Vec<Operand>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operand {
+    Number(i64),
+    Variable(Variable),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variable {
+    Date,
+    Time,
+    Weekday,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Eq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Values of the context variables at one moment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Context {
+    pub date: i64,
+    pub time: i64,
+    pub weekday: i64,
+}
+
+impl Context {
+    pub fn at(at: NaiveDateTime) -> Self {
+        Self {
+            // The date is the local midnight expressed as if it were UTC, so it compares
+            // equal to date literals regardless of the screen's timezone.
+            date: at
+                .date()
+                .and_time(NaiveTime::MIN)
+                .and_utc()
+                .timestamp_millis(),
+            time: i64::from(at.num_seconds_from_midnight()) * 1000
+                + i64::from(at.nanosecond() / 1_000_000),
+            weekday: i64::from(at.weekday().num_days_from_sunday()),
+        }
+    }
+}
+
+impl Expr {
+    /// Whether the predicate holds at the local time `at`.
+    pub fn evaluate(&self, at: NaiveDateTime) -> bool {
+        self.evaluate_in(&Context::at(at))
+    }
+
+    pub fn evaluate_in(&self, context: &Context) -> bool {
+        match self {
+            Expr::Bool(value) => *value,
+            Expr::Not(expr) => !expr.evaluate_in(context),
+            Expr::And(left, right) => left.evaluate_in(context) && right.evaluate_in(context),
+            Expr::Or(left, right) => left.evaluate_in(context) || right.evaluate_in(context),
+            Expr::Compare(left, comparison, right) => {
+                comparison.holds(left.value(context), right.value(context))
+            }
+            Expr::Between(value, min, max) => {
+                (min.value(context)..=max.value(context)).contains(&value.value(context))
+            }
+            Expr::In(value, candidates) => {
+                let value = value.value(context);
+                candidates
+                    .iter()
+                    .any(|candidate| candidate.value(context) == value)
+            }
+        }
+    }
+
+    /// Times of day, in milliseconds since midnight, at which the predicate may change its
+    /// value, in order and starting with 0. `$DATE` and `$WEEKDAY` are constant within a
+    /// day, so only comparisons with `$TIME` matter.
+    pub fn time_changes(&self) -> Vec<i64> {
+        let mut changes = vec![0];
+        self.collect_time_changes(&mut changes);
+        changes.retain(|time| (0..DAY_MS).contains(time));
+        changes.sort_unstable();
+        changes.dedup();
+        changes
+    }
+
+    fn collect_time_changes(&self, changes: &mut Vec<i64>) {
+        let time_literal = |operand: &Operand, other: &Operand| match (operand, other) {
+            (Operand::Variable(Variable::Time), Operand::Number(value)) => Some(*value),
+            _ => None,
+        };
+        match self {
+            Expr::Bool(_) => {}
+            Expr::Not(expr) => expr.collect_time_changes(changes),
+            Expr::And(left, right) | Expr::Or(left, right) => {
+                left.collect_time_changes(changes);
+                right.collect_time_changes(changes);
+            }
+            // A comparison flips at the literal or right after it, depending on the operator.
+            Expr::Compare(left, _, right) => {
+                if let Some(value) = time_literal(left, right).or(time_literal(right, left)) {
+                    changes.extend([value, value + 1]);
+                }
+            }
+            Expr::Between(value, min, max) => {
+                changes.extend(time_literal(value, min));
+                changes.extend(time_literal(value, max).map(|max| max + 1));
+            }
+            Expr::In(value, candidates) => {
+                for candidate in candidates {
+                    if let Some(time) = time_literal(value, candidate) {
+                        changes.extend([time, time + 1]);
+                    }
+                }
+            }
+        }
+    }
+
+    // Binding strength, used to parenthesise only where needed when printing.
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Or(..) => 1,
+            Expr::And(..) => 2,
+            Expr::Not(_) => 3,
+            _ => 4,
+        }
+    }
+
+    fn fmt_operand(&self, f: &mut fmt::Formatter<'_>, parent: u8) -> fmt::Result {
+        if self.precedence() <= parent {
+            write!(f, "({self})")
+        } else {
+            write!(f, "{self}")
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precedence = self.precedence();
+        match self {
+            Expr::Bool(true) => write!(f, "TRUE"),
+            Expr::Bool(false) => write!(f, "FALSE"),
+            Expr::Not(expr) => {
+                write!(f, "NOT ")?;
+                expr.fmt_operand(f, precedence - 1)
+            }
+            // Both operators are left-associative, so only a right operand of the same
+            // kind needs parentheses.
+            Expr::And(left, right) | Expr::Or(left, right) => {
+                left.fmt_operand(f, precedence - 1)?;
+                write!(f, " {} ", if precedence == 1 { "OR" } else { "AND" })?;
+                right.fmt_operand(f, precedence)
+            }
+            Expr::Compare(left, comparison, right) => write!(f, "{left} {comparison} {right}"),
+            Expr::Between(value, min, max) => write!(f, "{value} BETWEEN {{{min}, {max}}}"),
+            Expr::In(value, candidates) => {
+                let candidates: Vec<String> = candidates.iter().map(Operand::to_string).collect();
+                write!(f, "{value} IN {{{}}}", candidates.join(", "))
+            }
+        }
+    }
+}
+
+impl Operand {
+    fn value(&self, context: &Context) -> i64 {
+        match self {
+            Operand::Number(number) => *number,
+            Operand::Variable(Variable::Date) => context.date,
+            Operand::Variable(Variable::Time) => context.time,
+            Operand::Variable(Variable::Weekday) => context.weekday,
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Number(number) => write!(f, "{number}"),
+            Operand::Variable(variable) => write!(f, "{variable}"),
+        }
+    }
+}
+
+impl Variable {
+    // Why a literal compared with the variable can never match, if it can't.
+    fn check(&self, value: i64) -> Option<String> {
+        match self {
+            Variable::Time if !(0..=DAY_MS).contains(&value) => Some(format!(
+                "`{value}` is out of range, $TIME counts milliseconds since midnight (0 to {DAY_MS})"
+            )),
+            Variable::Weekday if !(0..=6).contains(&value) => Some(format!(
+                "`{value}` is out of range, $WEEKDAY goes from 0 (Sunday) to 6 (Saturday)"
+            )),
+            Variable::Date if (1..MIN_DATE_MS).contains(&value) => Some(format!(
+                "`{value}` looks like seconds, $DATE is a timestamp in milliseconds"
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Variable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Variable::Date => write!(f, "$DATE"),
+            Variable::Time => write!(f, "$TIME"),
+            Variable::Weekday => write!(f, "$WEEKDAY"),
+        }
+    }
+}
+
+impl Comparison {
+    fn holds(&self, left: i64, right: i64) -> bool {
+        match self {
+            Comparison::Eq => left == right,
+            Comparison::Lt => left < right,
+            Comparison::Le => left <= right,
+            Comparison::Gt => left > right,
+            Comparison::Ge => left >= right,
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Comparison::Eq => "=",
+            Comparison::Lt => "<",
+            Comparison::Le => "<=",
+            Comparison::Gt => ">",
+            Comparison::Ge => ">=",
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum TokenKind {
+    Number(i64),
+    Variable(String),
+    Word(String),
+    Symbol(&'static str),
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::Number(number) => write!(f, "{number}"),
+            TokenKind::Variable(name) => write!(f, "${name}"),
+            TokenKind::Word(word) => write!(f, "{word}"),
+            TokenKind::Symbol(symbol) => write!(f, "{symbol}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Token {
+    kind: TokenKind,
+    column: usize,
+}
+
+fn tokenize(predicate: &str) -> Result<Vec<Token>, PredicateError> {
+    const SYMBOLS: [&str; 10] = ["<=", ">=", "=", "<", ">", "{", "}", ",", "(", ")"];
+
+    let column = |offset: usize| predicate[..offset].chars().count() + 1;
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+    while offset < predicate.len() {
+        let rest = &predicate[offset..];
+        let Some(c) = rest.chars().next().filter(|c| !c.is_whitespace()) else {
+            offset += rest.chars().next().map_or(1, char::len_utf8);
+            continue;
+        };
+        if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol)) {
+            tokens.push(Token {
+                kind: TokenKind::Symbol(symbol),
+                column: column(offset),
+            });
+            offset += symbol.len();
+            continue;
+        }
+
+        let body = rest.strip_prefix('$').unwrap_or(rest);
+        let end = body
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(body.len());
+        let word = &body[..end];
+        let error = |message: String| PredicateError {
+            predicate: predicate.to_string(),
+            column: column(offset),
+            message,
+        };
+        let kind = if c == '$' {
+            if word.is_empty() {
+                return Err(error("expected a variable name after `$`".to_string()));
+            }
+            TokenKind::Variable(word.to_ascii_uppercase())
+        } else if word.is_empty() {
+            return Err(error(format!("unexpected `{c}`")));
+        } else if word.bytes().all(|b| b.is_ascii_digit()) {
+            TokenKind::Number(
+                word.parse()
+                    .map_err(|_| error(format!("`{word}` is too large")))?,
+            )
+        } else if c.is_ascii_digit() {
+            return Err(error(format!("`{word}` is not a number")));
+        } else {
+            TokenKind::Word(word.to_ascii_uppercase())
+        };
+        tokens.push(Token {
+            kind,
+            column: column(offset),
+        });
+        offset += rest.len() - body.len() + end;
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    predicate: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn error(&self, column: usize, message: String) -> PredicateError {
+        PredicateError {
+            predicate: self.predicate.to_string(),
+            column,
+            message,
+        }
+    }
+
+    // Reports what was expected at the current token, or at the end of the predicate.
+    fn unexpected(&self, token: Option<Token>, expected: &str) -> PredicateError {
+        match token {
+            Some(token) => self.error(
+                token.column,
+                format!("expected {expected} but found `{}`", token.kind),
+            ),
+            None => self.error(
+                self.predicate.chars().count() + 1,
+                format!("expected {expected} but the predicate ended"),
+            ),
+        }
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        let matches =
+            matches!(self.peek(), Some(Token { kind: TokenKind::Word(w), .. }) if w == word);
+        self.pos += usize::from(matches);
+        matches
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), PredicateError> {
+        match self.next() {
+            Some(Token {
+                kind: TokenKind::Symbol(s),
+                ..
+            }) if s == symbol => Ok(()),
+            other => Err(self.unexpected(other, &format!("`{symbol}`"))),
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, PredicateError> {
+        let mut expr = self.and()?;
+        while self.eat_word("OR") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, PredicateError> {
+        let mut expr = self.not()?;
+        while self.eat_word("AND") {
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr, PredicateError> {
+        if self.eat_word("NOT") {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        if self.eat_word("TRUE") {
+            return Ok(Expr::Bool(true));
+        }
+        if self.eat_word("FALSE") {
+            return Ok(Expr::Bool(false));
+        }
+        if matches!(
+            self.peek(),
+            Some(Token {
+                kind: TokenKind::Symbol("("),
+                ..
+            })
+        ) {
+            self.pos += 1;
+            let expr = self.expr()?;
+            self.expect(")")?;
+            return Ok(expr);
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, PredicateError> {
+        let left = self.operand()?;
+        if self.eat_word("BETWEEN") {
+            let bounds = self.set()?;
+            let [(min_column, min), (max_column, max)] = bounds[..] else {
+                let column = bounds.get(2).map_or(bounds[0].0, |(column, _)| *column);
+                return Err(self.error(column, "BETWEEN takes exactly two values".to_string()));
+            };
+            self.check(left, min, min_column)?;
+            self.check(left, max, max_column)?;
+            if let (Operand::Number(low), Operand::Number(high)) = (min, max) {
+                if low > high {
+                    return Err(self.error(
+                        min_column,
+                        format!("the lower bound `{low}` is greater than the upper bound `{high}`"),
+                    ));
+                }
+            }
+            return Ok(Expr::Between(left.1, min, max));
+        }
+        if self.eat_word("IN") {
+            let candidates = self.set()?;
+            for (column, candidate) in &candidates {
+                self.check(left, *candidate, *column)?;
+            }
+            return Ok(Expr::In(
+                left.1,
+                candidates.into_iter().map(|(_, operand)| operand).collect(),
+            ));
+        }
+
+        let comparison = match self.next() {
+            Some(Token {
+                kind: TokenKind::Symbol(symbol),
+                column,
+            }) => match symbol {
+                "=" => Comparison::Eq,
+                "<" => Comparison::Lt,
+                "<=" => Comparison::Le,
+                ">" => Comparison::Gt,
+                ">=" => Comparison::Ge,
+                _ => {
+                    return Err(self.error(
+                        column,
+                        format!("expected a comparison but found `{symbol}`"),
+                    ))
+                }
+            },
+            other => return Err(self.unexpected(other, "a comparison")),
+        };
+        let right = self.operand()?;
+        self.check(left, right.1, right.0)?;
+        self.check(right, left.1, left.0)?;
+        Ok(Expr::Compare(left.1, comparison, right.1))
+    }
+
+    // Rejects a literal that a variable can never take, which is almost always a typo.
+    fn check(
+        &self,
+        (_, variable): (usize, Operand),
+        literal: Operand,
+        column: usize,
+    ) -> Result<(), PredicateError> {
+        if let (Operand::Variable(variable), Operand::Number(value)) = (variable, literal) {
+            if let Some(message) = variable.check(value) {
+                return Err(self.error(column, message));
+            }
+        }
+        Ok(())
+    }
+
+    fn set(&mut self) -> Result<Vec<(usize, Operand)>, PredicateError> {
+        self.expect("{")?;
+        let mut values = vec![self.operand()?];
+        loop {
+            match self.next() {
+                Some(Token {
+                    kind: TokenKind::Symbol(","),
+                    ..
+                }) => values.push(self.operand()?),
+                Some(Token {
+                    kind: TokenKind::Symbol("}"),
+                    ..
+                }) => return Ok(values),
+                other => return Err(self.unexpected(other, "`,` or `}`")),
+            }
+        }
+    }
+
+    fn operand(&mut self) -> Result<(usize, Operand), PredicateError> {
+        match self.next() {
+            Some(Token {
+                kind: TokenKind::Number(number),
+                column,
+            }) => Ok((column, Operand::Number(number))),
+            Some(Token {
+                kind: TokenKind::Variable(name),
+                column,
+            }) => {
+                let variable = match name.as_str() {
+                    "DATE" => Variable::Date,
+                    "TIME" => Variable::Time,
+                    "WEEKDAY" => Variable::Weekday,
+                    _ => {
+                        return Err(self.error(
+                            column,
+                            format!(
+                                "unknown variable `${name}`, expected $DATE, $TIME or $WEEKDAY"
+                            ),
+                        ))
+                    }
+                };
+                Ok((column, Operand::Variable(variable)))
+            }
+            other => Err(self.unexpected(other, "a number or variable")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .unwrap()
+            .and_time(NaiveTime::parse_from_str(time, "%H:%M").unwrap())
+    }
+
+    #[test]
+    fn test_evaluate_should_use_local_date_time_and_weekday() {
+        // 2026-10-19 is a Monday.
+        let monday_morning = at("2026-10-19", "09:30");
+        let sunday_night = at("2026-10-18", "22:00");
+
+        let cases = [
+            ("TRUE", true, true),
+            ("false", false, false),
+            ("$WEEKDAY IN {1, 2, 3, 4, 5}", true, false),
+            ("NOT $WEEKDAY IN {0, 6}", true, false),
+            ("$TIME BETWEEN {32400000, 61200000}", true, false),
+            (
+                "$TIME >= 32400000 AND $TIME <= 61200000 OR $WEEKDAY = 0",
+                true,
+                true,
+            ),
+            ("$DATE >= 1792368000000", true, false),
+            (
+                "($WEEKDAY = 0 OR $WEEKDAY = 6) AND $TIME > 72000000",
+                false,
+                true,
+            ),
+        ];
+        for (predicate, on_monday, on_sunday) in cases {
+            assert_eq!(
+                evaluate(predicate, monday_morning).unwrap(),
+                on_monday,
+                "{predicate}"
+            );
+            assert_eq!(
+                evaluate(predicate, sunday_night).unwrap(),
+                on_sunday,
+                "{predicate}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_should_point_at_the_column_of_the_error() {
+        let cases = [
+            ("", 1, "expected a number or variable but the predicate ended"),
+            ("$TIME BETWEEN {1, 2, 3}", 22, "BETWEEN takes exactly two values"),
+            ("$WEEKDAY IN {1, 2", 18, "expected `,` or `}` but the predicate ended"),
+            ("$HOUR = 9", 1, "unknown variable `$HOUR`, expected $DATE, $TIME or $WEEKDAY"),
+            ("$TIME = 1 $TIME", 11, "expected `AND`, `OR` or the end but found `$TIME`"),
+            ("$TIME ~ 1", 7, "unexpected `~`"),
+            ("$TIME = 9am", 9, "`9am` is not a number"),
+            ("$TIME BETWEEN {32400000, 612000000}", 26, "`612000000` is out of range, $TIME counts milliseconds since midnight (0 to 86400000)"),
+            ("$WEEKDAY = 7", 12, "`7` is out of range, $WEEKDAY goes from 0 (Sunday) to 6 (Saturday)"),
+            ("$DATE >= 1792368000", 10, "`1792368000` looks like seconds, $DATE is a timestamp in milliseconds"),
+            ("$TIME BETWEEN {61200000, 32400000}", 16, "the lower bound `61200000` is greater than the upper bound `32400000`"),
+            ("NOT ($TIME < 1", 15, "expected `)` but the predicate ended"),
+        ];
+        for (predicate, column, message) in cases {
+            let error = parse(predicate).unwrap_err();
+            assert_eq!(
+                (error.column, error.message.as_str()),
+                (column, message),
+                "{predicate}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_predicate_error_should_underline_the_column() {
+        let error = parse("$WEEKDAY IN {1, 9}").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`9` is out of range, $WEEKDAY goes from 0 (Sunday) to 6 (Saturday) at column 17:\n  \
+             $WEEKDAY IN {1, 9}\n                  \
+             ^"
+        );
+    }
+
+    #[test]
+    fn test_display_should_print_canonical_form_that_parses_back() {
+        let cases = [
+            ("true", "TRUE"),
+            ("$weekday in {1,2 ,3}", "$WEEKDAY IN {1, 2, 3}"),
+            ("not ($time<1 or $time>=2)", "NOT ($TIME < 1 OR $TIME >= 2)"),
+            ("(($TIME = 1))", "$TIME = 1"),
+            (
+                "$TIME = 1 AND ($TIME = 2 OR $TIME = 3)",
+                "$TIME = 1 AND ($TIME = 2 OR $TIME = 3)",
+            ),
+            (
+                "$TIME = 1 AND $TIME = 2 OR $TIME = 3",
+                "$TIME = 1 AND $TIME = 2 OR $TIME = 3",
+            ),
+            (
+                "$TIME = 1 OR ($TIME = 2 OR $TIME = 3)",
+                "$TIME = 1 OR ($TIME = 2 OR $TIME = 3)",
+            ),
+            ("NOT NOT FALSE", "NOT NOT FALSE"),
+            (
+                "$DATE BETWEEN {1792368000000,1792454400000}",
+                "$DATE BETWEEN {1792368000000, 1792454400000}",
+            ),
+        ];
+        for (predicate, canonical) in cases {
+            let expr = parse(predicate).unwrap();
+            assert_eq!(expr.to_string(), canonical);
+            assert_eq!(parse(canonical).unwrap(), expr);
+        }
+    }
+}
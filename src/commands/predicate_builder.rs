@@ -0,0 +1,539 @@
+//! Schedules written as plain options, such as Monday to Friday from 09:00 to 17:00,
+//! compiled into playlist predicates, and predicates explained back in plain English.
+
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Timelike};
+
+use crate::commands::predicate::{Comparison, Context, Expr, Operand, Variable, DAY_MS};
+use crate::commands::CommandError;
+
+// Indexed by `$WEEKDAY`.
+const WEEKDAY_NAMES: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+/// Days of the week as `$WEEKDAY` values, written as comma-separated day names and ranges
+/// such as `mon-fri` or `sat,sun`. Ranges may wrap around the week, as in `fri-mon`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Weekdays(pub BTreeSet<i64>);
+
+impl FromStr for Weekdays {
+    type Err = CommandError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let day = |name: &str| {
+            let name = name.trim().to_lowercase();
+            WEEKDAY_NAMES
+                .iter()
+                .position(|day| name.len() >= 3 && day.to_lowercase().starts_with(&name))
+                .map(|day| day as i64)
+                .ok_or_else(|| {
+                    CommandError::InvalidArgument(format!(
+                        "unknown weekday \"{name}\", expected a name such as mon or monday"
+                    ))
+                })
+        };
+        let mut days = BTreeSet::new();
+        for part in s.split(',') {
+            match part.split_once('-') {
+                Some((first, last)) => {
+                    let (first, last) = (day(first)?, day(last)?);
+                    let mut current = first;
+                    days.insert(current);
+                    while current != last {
+                        current = (current + 1) % 7;
+                        days.insert(current);
+                    }
+                }
+                None => {
+                    days.insert(day(part)?);
+                }
+            }
+        }
+        Ok(Self(days))
+    }
+}
+
+/// A daily window written as `HH:MM-HH:MM`, in milliseconds since midnight. The start is
+/// included and the end is not. A window that ends before it starts runs past midnight.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TimeWindow {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl FromStr for TimeWindow {
+    type Err = CommandError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            CommandError::InvalidArgument(format!(
+                "time window must be \"HH:MM-HH:MM\", e.g. 09:00-17:00, got \"{s}\""
+            ))
+        };
+        let time = |time: &str| {
+            let time = time.trim();
+            if time == "24:00" {
+                return Ok(DAY_MS);
+            }
+            NaiveTime::parse_from_str(time, "%H:%M")
+                .map(|time| i64::from(time.num_seconds_from_midnight()) * 1000)
+                .map_err(|_| invalid())
+        };
+        let (start, end) = s.split_once('-').ok_or_else(invalid)?;
+        let (start, end) = (time(start)?, time(end)?);
+        if start == DAY_MS {
+            return Err(CommandError::InvalidArgument(format!(
+                "time window \"{s}\" starts at 24:00, start it at 00:00 instead"
+            )));
+        }
+        if start == end {
+            return Err(CommandError::InvalidArgument(format!(
+                "time window \"{s}\" is empty"
+            )));
+        }
+        Ok(Self { start, end })
+    }
+}
+
+/// A schedule made of optional restrictions that must all hold.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Schedule {
+    pub weekdays: Option<Weekdays>,
+    pub between: Option<TimeWindow>,
+    pub from_date: Option<NaiveDate>,
+    pub until_date: Option<NaiveDate>,
+    pub always: bool,
+}
+
+impl Schedule {
+    /// The predicate of the schedule, or `None` when nothing is set.
+    pub fn predicate(&self) -> Result<Option<String>, CommandError> {
+        if self.always {
+            return Ok(Some(Expr::Bool(true).to_string()));
+        }
+        if let (Some(from), Some(until)) = (self.from_date, self.until_date) {
+            if until < from {
+                return Err(CommandError::InvalidArgument(format!(
+                    "the schedule ends on {until}, before it starts on {from}"
+                )));
+            }
+        }
+
+        let mut restrictions = Vec::new();
+        if let Some(weekdays) = &self.weekdays {
+            let days: Vec<Operand> = weekdays.0.iter().copied().map(Operand::Number).collect();
+            restrictions.push(match days.as_slice() {
+                [day] => compare(Variable::Weekday, Comparison::Eq, *day),
+                _ => Expr::In(Operand::Variable(Variable::Weekday), days),
+            });
+        }
+        if let Some(TimeWindow { start, end }) = self.between {
+            restrictions.push(if start < end {
+                between(Variable::Time, start, end - 1)
+            } else {
+                Expr::Or(
+                    Box::new(compare(
+                        Variable::Time,
+                        Comparison::Ge,
+                        Operand::Number(start),
+                    )),
+                    Box::new(compare(
+                        Variable::Time,
+                        Comparison::Lt,
+                        Operand::Number(end),
+                    )),
+                )
+            });
+        }
+        let date = |date: NaiveDate| date.and_time(NaiveTime::MIN).and_utc().timestamp_millis();
+        match (self.from_date.map(date), self.until_date.map(date)) {
+            (Some(from), Some(until)) => restrictions.push(between(Variable::Date, from, until)),
+            (Some(from), None) => restrictions.push(compare(
+                Variable::Date,
+                Comparison::Ge,
+                Operand::Number(from),
+            )),
+            (None, Some(until)) => restrictions.push(compare(
+                Variable::Date,
+                Comparison::Le,
+                Operand::Number(until),
+            )),
+            (None, None) => {}
+        }
+
+        Ok(restrictions
+            .into_iter()
+            .reduce(|left, right| Expr::And(Box::new(left), Box::new(right)))
+            .map(|expr| expr.to_string()))
+    }
+}
+
+fn compare(variable: Variable, comparison: Comparison, value: Operand) -> Expr {
+    Expr::Compare(Operand::Variable(variable), comparison, value)
+}
+
+fn between(variable: Variable, min: i64, max: i64) -> Expr {
+    Expr::Between(
+        Operand::Variable(variable),
+        Operand::Number(min),
+        Operand::Number(max),
+    )
+}
+
+/// Describes when a predicate shows its playlist, e.g. "Shown on Monday to Friday, from
+/// 09:00 to 17:00."
+pub fn explain(expr: &Expr) -> String {
+    match constant(expr) {
+        Some(true) => "Always shown.".to_string(),
+        Some(false) => "Never shown.".to_string(),
+        None => format!("Shown {}.", phrase(expr)),
+    }
+}
+
+fn phrase(expr: &Expr) -> String {
+    if let Some(value) = constant(expr) {
+        return if value { "always" } else { "never" }.to_string();
+    }
+    match variables(expr).as_slice() {
+        [Variable::Weekday] => return weekdays_phrase(expr),
+        [Variable::Time] => return time_phrase(expr),
+        _ => {}
+    }
+    match expr {
+        Expr::And(..) => {
+            let mut parts = Vec::new();
+            conjuncts(expr, &mut parts);
+            parts
+                .iter()
+                .map(|part| match part {
+                    Expr::Or(..) if variables(part).len() > 1 => format!("({})", phrase(part)),
+                    _ => phrase(part),
+                })
+                .collect::<Vec<String>>()
+                .join(", ")
+        }
+        Expr::Or(left, right) => format!("{} or {}", phrase(left), phrase(right)),
+        Expr::Not(expr) => format!("except {}", phrase(expr)),
+        _ => date_phrase(expr).unwrap_or_else(|| format!("when `{expr}`")),
+    }
+}
+
+fn conjuncts<'a>(expr: &'a Expr, parts: &mut Vec<&'a Expr>) {
+    match expr {
+        Expr::And(left, right) => {
+            conjuncts(left, parts);
+            conjuncts(right, parts);
+        }
+        _ => parts.push(expr),
+    }
+}
+
+// The value of an expression that uses no variable.
+fn constant(expr: &Expr) -> Option<bool> {
+    variables(expr).is_empty().then(|| {
+        expr.evaluate_in(&Context {
+            date: 0,
+            time: 0,
+            weekday: 0,
+        })
+    })
+}
+
+fn variables(expr: &Expr) -> Vec<Variable> {
+    fn collect(expr: &Expr, variables: &mut Vec<Variable>) {
+        let mut operand = |operand: &Operand| {
+            if let Operand::Variable(variable) = operand {
+                if !variables.contains(variable) {
+                    variables.push(*variable);
+                }
+            }
+        };
+        match expr {
+            Expr::Bool(_) => {}
+            Expr::Not(expr) => collect(expr, variables),
+            Expr::And(left, right) | Expr::Or(left, right) => {
+                collect(left, variables);
+                collect(right, variables);
+            }
+            Expr::Compare(left, _, right) => {
+                operand(left);
+                operand(right);
+            }
+            Expr::Between(value, min, max) => {
+                operand(value);
+                operand(min);
+                operand(max);
+            }
+            Expr::In(value, candidates) => {
+                operand(value);
+                candidates.iter().for_each(operand);
+            }
+        }
+    }
+    let mut variables = Vec::new();
+    collect(expr, &mut variables);
+    variables
+}
+
+// Days of an expression on `$WEEKDAY` alone, as "on Monday to Friday".
+fn weekdays_phrase(expr: &Expr) -> String {
+    // Weeks start on Monday here, so that weekends read as one run.
+    let days: Vec<i64> = [1, 2, 3, 4, 5, 6, 0]
+        .into_iter()
+        .filter(|weekday| {
+            expr.evaluate_in(&Context {
+                date: 0,
+                time: 0,
+                weekday: *weekday,
+            })
+        })
+        .collect();
+    if days.len() == 7 {
+        return "every day".to_string();
+    }
+    if days.is_empty() {
+        return "never".to_string();
+    }
+    let position = |weekday: i64| (weekday + 6) % 7;
+    let mut runs: Vec<(i64, i64)> = Vec::new();
+    for day in days {
+        match runs.last_mut() {
+            Some(run) if position(run.1) + 1 == position(day) => run.1 = day,
+            _ => runs.push((day, day)),
+        }
+    }
+    let names: Vec<String> = runs
+        .into_iter()
+        .flat_map(|(first, last)| {
+            let name = |day: i64| WEEKDAY_NAMES[day as usize].to_string();
+            match position(last) - position(first) {
+                0 => vec![name(first)],
+                1 => vec![name(first), name(last)],
+                _ => vec![format!("{} to {}", name(first), name(last))],
+            }
+        })
+        .collect();
+    format!("on {}", join(&names, "and"))
+}
+
+// Times of day of an expression on `$TIME` alone, as "from 09:00 to 17:00".
+fn time_phrase(expr: &Expr) -> String {
+    let holds = |time: i64| {
+        expr.evaluate_in(&Context {
+            date: 0,
+            time,
+            weekday: 0,
+        })
+    };
+    let changes = expr.time_changes();
+    let mut windows: Vec<(i64, i64)> = Vec::new();
+    for (index, start) in changes.iter().enumerate() {
+        if !holds(*start) {
+            continue;
+        }
+        let end = changes.get(index + 1).copied().unwrap_or(DAY_MS);
+        match windows.last_mut() {
+            Some(window) if window.1 == *start => window.1 = end,
+            _ => windows.push((*start, end)),
+        }
+    }
+    // A window that reaches midnight carries on with the one that starts at midnight.
+    if windows.len() > 1 && windows[0].0 == 0 && windows[windows.len() - 1].1 == DAY_MS {
+        let first = windows.remove(0);
+        if let Some(last) = windows.last_mut() {
+            last.1 = first.1;
+        }
+    }
+    match windows.as_slice() {
+        [] => "never".to_string(),
+        [(0, DAY_MS)] => "all day".to_string(),
+        _ => {
+            let windows: Vec<String> = windows
+                .iter()
+                .map(|(start, end)| match end - start {
+                    1 => format!("at {}", clock(*start)),
+                    _ => format!("from {} to {}", clock(*start), clock(*end)),
+                })
+                .collect();
+            join(&windows, "and")
+        }
+    }
+}
+
+// Dates of a single comparison of `$DATE` with literals, as "from 2026-12-01".
+fn date_phrase(expr: &Expr) -> Option<String> {
+    let date = |operand: &Operand| match operand {
+        Operand::Number(value) => Some(calendar(*value)),
+        Operand::Variable(_) => None,
+    };
+    let is_date = |operand: &Operand| *operand == Operand::Variable(Variable::Date);
+    match expr {
+        Expr::Compare(left, comparison, right) => {
+            // Puts `$DATE` on the left, as in `$DATE >= 1796083200000`.
+            let (comparison, value) = if is_date(left) {
+                (*comparison, date(right)?)
+            } else if is_date(right) {
+                let flipped = match comparison {
+                    Comparison::Eq => Comparison::Eq,
+                    Comparison::Lt => Comparison::Gt,
+                    Comparison::Le => Comparison::Ge,
+                    Comparison::Gt => Comparison::Lt,
+                    Comparison::Ge => Comparison::Le,
+                };
+                (flipped, date(left)?)
+            } else {
+                return None;
+            };
+            Some(match comparison {
+                Comparison::Eq => format!("on {value}"),
+                Comparison::Lt => format!("before {value}"),
+                Comparison::Le => format!("until {value}"),
+                Comparison::Gt => format!("after {value}"),
+                Comparison::Ge => format!("from {value}"),
+            })
+        }
+        Expr::Between(value, min, max) if is_date(value) => {
+            Some(format!("from {} to {}", date(min)?, date(max)?))
+        }
+        Expr::In(value, candidates) if is_date(value) => {
+            let dates = candidates.iter().map(date).collect::<Option<Vec<_>>>()?;
+            Some(format!("on {}", join(&dates, "or")))
+        }
+        _ => None,
+    }
+}
+
+// A `$TIME` value as a wall-clock time, as precise as it needs to be.
+fn clock(time: i64) -> String {
+    let (hours, minutes) = (time / 3_600_000, time / 60_000 % 60);
+    let (seconds, milliseconds) = (time / 1000 % 60, time % 1000);
+    if milliseconds != 0 {
+        format!("{hours:02}:{minutes:02}:{seconds:02}.{milliseconds:03}")
+    } else if seconds != 0 {
+        format!("{hours:02}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{hours:02}:{minutes:02}")
+    }
+}
+
+// A `$DATE` value as a date, or as a date and time when it isn't a midnight.
+fn calendar(date: i64) -> String {
+    match DateTime::from_timestamp_millis(date) {
+        Some(at) if at.time() == NaiveTime::MIN => at.format("%Y-%m-%d").to_string(),
+        Some(at) => at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        None => date.to_string(),
+    }
+}
+
+// Joins as "a, b and c".
+fn join(items: &[String], conjunction: &str) -> String {
+    match items {
+        [] => String::new(),
+        [item] => item.clone(),
+        [init @ .., last] => format!("{} {conjunction} {last}", init.join(", ")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::predicate;
+
+    #[test]
+    fn test_schedule_should_compile_into_predicate() {
+        let schedule = Schedule {
+            weekdays: Some("mon-fri".parse().unwrap()),
+            between: Some("09:00-17:00".parse().unwrap()),
+            from_date: NaiveDate::from_ymd_opt(2026, 12, 1),
+            until_date: NaiveDate::from_ymd_opt(2026, 12, 24),
+            always: false,
+        };
+        assert_eq!(
+            schedule.predicate().unwrap().unwrap(),
+            "$WEEKDAY IN {1, 2, 3, 4, 5} AND $TIME BETWEEN {32400000, 61199999} \
+             AND $DATE BETWEEN {1796083200000, 1798070400000}"
+        );
+
+        let overnight = Schedule {
+            weekdays: Some("fri-sun".parse().unwrap()),
+            between: Some("22:00-06:00".parse().unwrap()),
+            ..Schedule::default()
+        };
+        assert_eq!(
+            overnight.predicate().unwrap().unwrap(),
+            "$WEEKDAY IN {0, 5, 6} AND ($TIME >= 79200000 OR $TIME < 21600000)"
+        );
+
+        let always = Schedule {
+            always: true,
+            ..Schedule::default()
+        };
+        assert_eq!(always.predicate().unwrap().unwrap(), "TRUE");
+        assert_eq!(Schedule::default().predicate().unwrap(), None);
+
+        let backwards = Schedule {
+            from_date: NaiveDate::from_ymd_opt(2026, 12, 24),
+            until_date: NaiveDate::from_ymd_opt(2026, 12, 1),
+            ..Schedule::default()
+        };
+        assert!(matches!(
+            backwards.predicate(),
+            Err(CommandError::InvalidArgument(_))
+        ));
+        assert!("mo-fri".parse::<Weekdays>().is_err());
+        assert!("9-17".parse::<TimeWindow>().is_err());
+        assert!("09:00-09:00".parse::<TimeWindow>().is_err());
+    }
+
+    #[test]
+    fn test_explain_should_describe_compiled_and_written_predicates() {
+        let explained = |predicate: &str| explain(&predicate::parse(predicate).unwrap());
+
+        let schedule = Schedule {
+            weekdays: Some("mon-fri".parse().unwrap()),
+            between: Some("09:00-17:00".parse().unwrap()),
+            from_date: NaiveDate::from_ymd_opt(2026, 12, 1),
+            until_date: NaiveDate::from_ymd_opt(2026, 12, 24),
+            always: false,
+        };
+        assert_eq!(
+            explained(&schedule.predicate().unwrap().unwrap()),
+            "Shown on Monday to Friday, from 09:00 to 17:00, from 2026-12-01 to 2026-12-24."
+        );
+        assert_eq!(
+            explained("$WEEKDAY IN {0, 5, 6} AND ($TIME >= 79200000 OR $TIME < 21600000)"),
+            "Shown on Friday to Sunday, from 22:00 to 06:00."
+        );
+        assert_eq!(explained("TRUE"), "Always shown.");
+        assert_eq!(explained("NOT TRUE OR FALSE"), "Never shown.");
+        assert_eq!(
+            explained("NOT $WEEKDAY IN {1, 2, 3, 4, 5}"),
+            "Shown on Saturday and Sunday."
+        );
+        assert_eq!(
+            explained("$WEEKDAY IN {1, 3, 5} AND $TIME BETWEEN {32400000, 61200000}"),
+            "Shown on Monday, Wednesday and Friday, from 09:00 to 17:00:00.001."
+        );
+        assert_eq!(
+            explained("1796083200000 <= $DATE AND NOT $DATE = 1798156800000"),
+            "Shown from 2026-12-01, except on 2026-12-25."
+        );
+        assert_eq!(
+            explained("$TIME < 43200000 OR $WEEKDAY = 0"),
+            "Shown from 00:00 to 12:00 or on Sunday."
+        );
+        assert_eq!(
+            explained("$WEEKDAY = $TIME AND $DATE > 0"),
+            "Shown when `$WEEKDAY = $TIME`, after 1970-01-01."
+        );
+    }
+}
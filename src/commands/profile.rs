@@ -0,0 +1,180 @@
+use serde_json::json;
+
+use crate::authentication::{
+    migrate_credentials, Authentication, AuthenticationError, CredentialBackend, ProfileStore,
+    API_BASE_URL, DEFAULT_PROFILE,
+};
+use crate::commands::{CommandError, Profiles};
+
+#[derive(Default)]
+pub struct ProfileCommand {}
+
+impl ProfileCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn list(&self) -> Result<Profiles, CommandError> {
+        let store = ProfileStore::load()?;
+        let active = store.selected();
+
+        let mut profiles = Vec::new();
+        if !store.profiles.contains_key(DEFAULT_PROFILE) {
+            profiles.push(json!({
+                "name": DEFAULT_PROFILE,
+                "url": API_BASE_URL,
+                "active": active == DEFAULT_PROFILE,
+                "logged_in": Authentication::has_stored_token(DEFAULT_PROFILE)?,
+            }));
+        }
+        for (name, profile) in &store.profiles {
+            profiles.push(json!({
+                "name": name,
+                "url": profile.url,
+                "active": active == *name,
+                "logged_in": Authentication::has_stored_token(name)?,
+            }));
+        }
+
+        Ok(Profiles::new(serde_json::Value::Array(profiles)))
+    }
+
+    pub fn use_profile(&self, name: &str) -> Result<(), CommandError> {
+        let mut store = ProfileStore::load()?;
+        if !store.contains(name) {
+            return Err(AuthenticationError::ProfileNotFound(name.to_string()).into());
+        }
+
+        store.active = Some(name.to_string());
+        store.save()?;
+        Ok(())
+    }
+
+    pub fn remove(&self, name: &str) -> Result<(), CommandError> {
+        let mut store = ProfileStore::load()?;
+        if !store.contains(name) {
+            return Err(AuthenticationError::ProfileNotFound(name.to_string()).into());
+        }
+
+        Authentication::remove_token(name)?;
+        store = ProfileStore::load()?;
+        store.profiles.remove(name);
+        if store.active.as_deref() == Some(name) {
+            store.active = None;
+        }
+        store.save()?;
+        Ok(())
+    }
+
+    /// Moves plaintext tokens into `backend`, or into the keyring when it is reachable and
+    /// the encrypted file otherwise.
+    pub fn migrate(&self, backend: Option<CredentialBackend>) -> Result<Vec<String>, CommandError> {
+        let backend = backend.unwrap_or_else(CredentialBackend::detect);
+        Ok(migrate_credentials(backend)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+    use std::fs;
+
+    use envtestkit::lock::lock_test;
+    use envtestkit::set_env;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::authentication::Profile;
+
+    fn save_acme_profile() {
+        let mut store = ProfileStore::default();
+        store.profiles.insert(
+            "acme".to_string(),
+            Profile {
+                url: "https://api.screenlyappstage.com".to_string(),
+                token: Some("acme_token".to_string()),
+                ..Default::default()
+            },
+        );
+        store.save().unwrap();
+    }
+
+    #[test]
+    fn test_list_profiles_should_include_default_and_stored_profiles() {
+        let tmp_dir = tempdir().unwrap();
+        let _lock = lock_test();
+        let _test = set_env(OsString::from("HOME"), tmp_dir.path().to_str().unwrap());
+        let _backend = set_env(OsString::from("SCREENLY_CREDENTIAL_BACKEND"), "file");
+        fs::write(tmp_dir.path().join(".screenly"), "token").unwrap();
+        save_acme_profile();
+
+        let profiles = ProfileCommand::new().list().unwrap();
+        assert_eq!(
+            profiles.value,
+            json!([
+                {"name": "default", "url": API_BASE_URL, "active": true, "logged_in": true},
+                {"name": "acme", "url": "https://api.screenlyappstage.com", "active": false, "logged_in": true},
+            ])
+        );
+    }
+
+    #[test]
+    fn test_use_profile_should_set_active_profile() {
+        let tmp_dir = tempdir().unwrap();
+        let _lock = lock_test();
+        let _test = set_env(OsString::from("HOME"), tmp_dir.path().to_str().unwrap());
+        save_acme_profile();
+
+        let command = ProfileCommand::new();
+        command.use_profile("acme").unwrap();
+        assert_eq!(
+            ProfileStore::load().unwrap().active,
+            Some("acme".to_string())
+        );
+        assert!(command.use_profile("globex").is_err());
+    }
+
+    #[test]
+    fn test_remove_profile_should_remove_profile_and_reset_active_profile() {
+        let tmp_dir = tempdir().unwrap();
+        let _lock = lock_test();
+        let _test = set_env(OsString::from("HOME"), tmp_dir.path().to_str().unwrap());
+        let _backend = set_env(OsString::from("SCREENLY_CREDENTIAL_BACKEND"), "file");
+        save_acme_profile();
+
+        let command = ProfileCommand::new();
+        command.use_profile("acme").unwrap();
+        command.remove("acme").unwrap();
+
+        let store = ProfileStore::load().unwrap();
+        assert!(!store.profiles.contains_key("acme"));
+        assert_eq!(store.active, None);
+    }
+
+    #[test]
+    fn test_migrate_should_move_plaintext_tokens_to_encrypted_file() {
+        let tmp_dir = tempdir().unwrap();
+        let _lock = lock_test();
+        let _test = set_env(OsString::from("HOME"), tmp_dir.path().to_str().unwrap());
+        let _passphrase = set_env(
+            OsString::from("SCREENLY_CREDENTIAL_PASSPHRASE"),
+            "passphrase",
+        );
+        fs::write(tmp_dir.path().join(".screenly"), "token").unwrap();
+        save_acme_profile();
+
+        let migrated = ProfileCommand::new()
+            .migrate(Some(CredentialBackend::EncryptedFile))
+            .unwrap();
+        assert_eq!(migrated, vec!["default".to_string(), "acme".to_string()]);
+        assert!(!tmp_dir.path().join(".screenly").exists());
+
+        let store = ProfileStore::load().unwrap();
+        assert_eq!(
+            store.credential_backend,
+            Some(CredentialBackend::EncryptedFile)
+        );
+        assert_eq!(store.profiles.get("acme").unwrap().token, None);
+        assert!(Authentication::has_stored_token("acme").unwrap());
+    }
+}
@@ -0,0 +1,331 @@
+use std::collections::hash_map::RandomState;
+use std::env;
+use std::hash::{BuildHasher, Hasher};
+use std::thread;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use log::{debug, warn};
+use reqwest::blocking::{RequestBuilder, Response};
+use reqwest::header::RETRY_AFTER;
+use reqwest::StatusCode;
+
+use crate::authentication::Authentication;
+use crate::commands::CommandError;
+
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_BASE_DELAY_MS: u64 = 500;
+const DEFAULT_MAX_DELAY_MS: u64 = 30_000;
+
+/// Controls how the HTTP helpers retry rate-limited and temporarily failing requests.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Also retry POST and PATCH requests on gateway errors and connection failures.
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_delay: Duration::from_millis(DEFAULT_BASE_DELAY_MS),
+            max_delay: Duration::from_millis(DEFAULT_MAX_DELAY_MS),
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Reads `SCREENLY_MAX_RETRIES`, `SCREENLY_RETRY_BASE_DELAY_MS`,
+    /// `SCREENLY_RETRY_MAX_DELAY_MS` and `SCREENLY_RETRY_NON_IDEMPOTENT`.
+    /// Unset or invalid values fall back to the defaults.
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        Self {
+            max_retries: env_value("SCREENLY_MAX_RETRIES").unwrap_or(default.max_retries),
+            base_delay: env_value("SCREENLY_RETRY_BASE_DELAY_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(default.base_delay),
+            max_delay: env_value("SCREENLY_RETRY_MAX_DELAY_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(default.max_delay),
+            retry_non_idempotent: env_value("SCREENLY_RETRY_NON_IDEMPOTENT")
+                .unwrap_or(default.retry_non_idempotent),
+        }
+    }
+
+    /// 429 means the request was not processed, so it is retried for every method.
+    /// Gateway errors are only retried for idempotent methods unless configured otherwise.
+    fn should_retry_status(&self, status: StatusCode, idempotent: bool) -> bool {
+        match status {
+            StatusCode::TOO_MANY_REQUESTS => true,
+            StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT => idempotent || self.retry_non_idempotent,
+            _ => false,
+        }
+    }
+
+    fn should_retry_error(&self, error: &reqwest::Error, idempotent: bool) -> bool {
+        (error.is_connect() || error.is_timeout()) && (idempotent || self.retry_non_idempotent)
+    }
+
+    /// Exponential backoff with equal jitter: half of the delay is fixed, the other half random.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        let half = exponential / 2;
+        half + half.mul_f64(jitter())
+    }
+}
+
+/// Sends the request produced by `build` and retries it according to the configured policy.
+/// `build` is called once per attempt because streamed bodies such as multipart
+/// uploads can't be cloned. Requests go through the configured cassette, if any.
+pub fn send_with_retry<F>(
+    authentication: &Authentication,
+    idempotent: bool,
+    build: F,
+) -> Result<Response, CommandError>
+where
+    F: Fn() -> Result<RequestBuilder, CommandError>,
+{
+    let policy = &authentication.config.retry;
+    let mut attempt = 0;
+    loop {
+        let can_retry = attempt < policy.max_retries;
+        let sent = match &authentication.config.cassette {
+            Some(cassette) => cassette.send(build()?, &authentication.token)?,
+            None => build()?.send(),
+        };
+        let delay = match sent {
+            Ok(response) => {
+                let status = response.status();
+                if !can_retry || !policy.should_retry_status(status, idempotent) {
+                    return Ok(response);
+                }
+                let delay = retry_after(&response)
+                    .map(|delay| delay.min(policy.max_delay))
+                    .unwrap_or_else(|| policy.backoff(attempt));
+                warn!(
+                    "Request failed with status {}. Retrying in {:.1}s.",
+                    status.as_u16(),
+                    delay.as_secs_f64()
+                );
+                delay
+            }
+            Err(e) if can_retry && policy.should_retry_error(&e, idempotent) => {
+                let delay = policy.backoff(attempt);
+                warn!(
+                    "Request failed: {e}. Retrying in {:.1}s.",
+                    delay.as_secs_f64()
+                );
+                delay
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        attempt += 1;
+        debug!("Retry attempt {attempt} of {}", policy.max_retries);
+        thread::sleep(delay);
+    }
+}
+
+/// Parses `Retry-After` given either in seconds or as an HTTP date.
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    parse_retry_after(value, Utc::now())
+}
+
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    if let Ok(seconds) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value.trim()).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - now)
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+fn env_value<T: std::str::FromStr>(name: &str) -> Option<T> {
+    let value = env::var(name).ok()?;
+    match value.parse() {
+        Ok(value) => Some(value),
+        Err(_) => {
+            warn!("Ignoring invalid value of {name}: {value}");
+            None
+        }
+    }
+}
+
+// A random fraction in [0, 1). Randomly seeded hashers are good enough for jitter.
+fn jitter() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(0);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+
+    use envtestkit::lock::lock_test;
+    use envtestkit::set_env;
+    use httpmock::Method::{GET, POST};
+    use httpmock::MockServer;
+
+    use super::*;
+    use crate::authentication::Config;
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 2,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            retry_non_idempotent: false,
+        }
+    }
+
+    fn with_policy(mock_server: &MockServer, retry: RetryPolicy) -> Authentication {
+        let config = Config {
+            retry,
+            ..Config::new(mock_server.base_url())
+        };
+        Authentication::new_with_config(config, "token")
+    }
+
+    #[test]
+    fn test_send_with_retry_should_retry_idempotent_request_on_service_unavailable() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method(GET).path("/v4/screens");
+            then.status(503);
+        });
+
+        let authentication = with_policy(&mock_server, fast_policy());
+        let client = authentication.build_client().unwrap();
+        let url = format!("{}/v4/screens", mock_server.base_url());
+        let response = send_with_retry(&authentication, true, || Ok(client.get(&url))).unwrap();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        mock.assert_calls(3);
+    }
+
+    #[test]
+    fn test_send_with_retry_should_not_retry_post_on_gateway_error_by_default() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method(POST).path("/v4/assets");
+            then.status(502);
+        });
+
+        let authentication = with_policy(&mock_server, fast_policy());
+        let client = authentication.build_client().unwrap();
+        let url = format!("{}/v4/assets", mock_server.base_url());
+        send_with_retry(&authentication, false, || Ok(client.post(&url))).unwrap();
+        mock.assert_calls(1);
+
+        let policy = RetryPolicy {
+            retry_non_idempotent: true,
+            ..fast_policy()
+        };
+        let authentication = with_policy(&mock_server, policy);
+        send_with_retry(&authentication, false, || Ok(client.post(&url))).unwrap();
+        mock.assert_calls(4);
+    }
+
+    #[test]
+    fn test_send_with_retry_should_retry_rate_limited_post_after_retry_after() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method(POST).path("/v4/assets");
+            then.status(429).header("Retry-After", "0");
+        });
+
+        let authentication = with_policy(&mock_server, fast_policy());
+        let client = authentication.build_client().unwrap();
+        let url = format!("{}/v4/assets", mock_server.base_url());
+        let response = send_with_retry(&authentication, false, || Ok(client.post(&url))).unwrap();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        mock.assert_calls(3);
+    }
+
+    #[test]
+    fn test_send_with_retry_should_not_retry_client_errors() {
+        let mock_server = MockServer::start();
+        let mock = mock_server.mock(|when, then| {
+            when.method(GET).path("/v4/screens");
+            then.status(404);
+        });
+
+        let authentication = with_policy(&mock_server, fast_policy());
+        let client = authentication.build_client().unwrap();
+        let url = format!("{}/v4/screens", mock_server.base_url());
+        send_with_retry(&authentication, true, || Ok(client.get(&url))).unwrap();
+        mock.assert_calls(1);
+    }
+
+    #[test]
+    fn test_backoff_should_stay_within_exponential_bounds() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(1000),
+            retry_non_idempotent: false,
+        };
+
+        for (attempt, ceiling) in [(0, 100), (1, 200), (2, 400), (5, 1000)] {
+            let delay = policy.backoff(attempt);
+            assert!(delay >= Duration::from_millis(ceiling / 2));
+            assert!(delay <= Duration::from_millis(ceiling));
+        }
+    }
+
+    #[test]
+    fn test_parse_retry_after_should_accept_seconds_and_http_dates() {
+        let now = DateTime::parse_from_rfc3339("2024-05-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 01 May 2024 00:00:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Tue, 30 Apr 2024 00:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_retry_policy_from_env_should_override_defaults() {
+        let _lock = lock_test();
+        let _retries = set_env(OsString::from("SCREENLY_MAX_RETRIES"), "5");
+        let _base = set_env(OsString::from("SCREENLY_RETRY_BASE_DELAY_MS"), "100");
+        let _max = set_env(OsString::from("SCREENLY_RETRY_MAX_DELAY_MS"), "invalid");
+        let _non_idempotent = set_env(OsString::from("SCREENLY_RETRY_NON_IDEMPOTENT"), "true");
+
+        assert_eq!(
+            RetryPolicy::from_env(),
+            RetryPolicy {
+                max_retries: 5,
+                base_delay: Duration::from_millis(100),
+                max_delay: Duration::from_millis(DEFAULT_MAX_DELAY_MS),
+                retry_non_idempotent: true,
+            }
+        );
+    }
+}
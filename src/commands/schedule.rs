@@ -0,0 +1,462 @@
+//! Which playlists reach a screen, which of them should be playing at a given time, and
+//! when a predicate puts a playlist on air over a range of days.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use log::warn;
+use serde_json::json;
+
+use crate::authentication::Authentication;
+use crate::commands;
+use crate::commands::pagination::{self, Pagination};
+use crate::commands::predicate::{Expr, DAY_MS};
+use crate::commands::{label, predicate, CommandError, OnAirIntervals, ScreenPlaylists};
+
+/// Longest range of days `simulate` accepts.
+const MAX_SIMULATED_DAYS: i64 = 366;
+
+/// An IANA timezone such as `Europe/London`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timezone(pub Tz);
+
+impl FromStr for Timezone {
+    type Err = CommandError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Self).map_err(|_| {
+            CommandError::InvalidArgument(format!(
+                "unknown timezone \"{s}\", expected an IANA name such as Europe/London"
+            ))
+        })
+    }
+}
+
+impl PartialOrd for Timezone {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Timezone {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.name().cmp(other.0.name())
+    }
+}
+
+/// The wall-clock time of a screen in `timezone`, or in UTC when the timezone is missing or
+/// unknown.
+pub fn local_time(timezone: &serde_json::Value, at: DateTime<Utc>) -> NaiveDateTime {
+    match timezone
+        .as_str()
+        .and_then(|timezone| timezone.parse::<Tz>().ok())
+    {
+        Some(timezone) => at.with_timezone(&timezone).naive_local(),
+        None => at.naive_utc(),
+    }
+}
+
+/// Whether a playlist is enabled and its predicate holds at the local time `at`.
+///
+/// A missing predicate always holds. A predicate that doesn't parse never does, as the
+/// player cannot evaluate it either.
+pub fn is_scheduled(playlist: &serde_json::Value, at: NaiveDateTime) -> bool {
+    if !playlist["is_enabled"].as_bool().unwrap_or(false) {
+        return false;
+    }
+    let Some(expression) = playlist["predicate"].as_str() else {
+        return true;
+    };
+    predicate::evaluate(expression, at).unwrap_or_else(|e| {
+        warn!(
+            "Playlist {} is never shown, its predicate is invalid: {e}",
+            playlist["id"]
+        );
+        false
+    })
+}
+
+/// The playlists that should be playing at the local time `at`: the scheduled ones, where
+/// scheduled priority playlists take over from the others.
+pub fn playing<'a>(
+    playlists: impl IntoIterator<Item = &'a serde_json::Value>,
+    at: NaiveDateTime,
+) -> Vec<&'a serde_json::Value> {
+    let scheduled: Vec<&serde_json::Value> = playlists
+        .into_iter()
+        .filter(|playlist| is_scheduled(playlist, at))
+        .collect();
+    let priority: Vec<&serde_json::Value> = scheduled
+        .iter()
+        .copied()
+        .filter(|playlist| playlist["priority"].as_bool().unwrap_or(false))
+        .collect();
+    if priority.is_empty() {
+        scheduled
+    } else {
+        priority
+    }
+}
+
+/// Lists the playlists that reach a screen through its labels, with the labels they come
+/// through and whether they are scheduled and playing at `at` in the screen's timezone.
+pub fn screen_playlists(
+    authentication: &Authentication,
+    screen_id: &str,
+    at: DateTime<Utc>,
+) -> Result<ScreenPlaylists, CommandError> {
+    let screens = commands::get(
+        authentication,
+        &format!("v4/screens?select=id,timezone&id=eq.{screen_id}"),
+    )?;
+    let Some(screen) = screens.as_array().and_then(|screens| screens.first()) else {
+        return Err(CommandError::ResourceNotFound(
+            "screen".to_string(),
+            screen_id.to_string(),
+        ));
+    };
+    let at = local_time(&screen["timezone"], at);
+
+    let label_ids = label::screen_label_ids(authentication, screen_id)?;
+    if label_ids.is_empty() {
+        return Ok(ScreenPlaylists::new(json!([])));
+    }
+    let label_ids = label_ids.join(",");
+    let all = |endpoint: &str| pagination::get_all(authentication, endpoint, Pagination::default());
+    let links = all(&format!(
+        "v4/labels/playlists?select=label_id,playlist_id&label_id=in.({label_ids})"
+    ))?;
+    let labels = all(&format!("v4/labels?select=id,name&id=in.({label_ids})"))?;
+    let label_names: BTreeMap<&str, &str> = rows(&labels)
+        .filter_map(|label| Some((label["id"].as_str()?, label["name"].as_str()?)))
+        .collect();
+    let mut playlist_labels: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for link in rows(&links) {
+        if let (Some(playlist_id), Some(label_id)) =
+            (link["playlist_id"].as_str(), link["label_id"].as_str())
+        {
+            let name = label_names.get(label_id).copied().unwrap_or(label_id);
+            playlist_labels.entry(playlist_id).or_default().insert(name);
+        }
+    }
+    if playlist_labels.is_empty() {
+        return Ok(ScreenPlaylists::new(json!([])));
+    }
+
+    let playlist_ids: Vec<&str> = playlist_labels.keys().copied().collect();
+    let playlists = all(&format!(
+        "v4/playlists?select=id,title,is_enabled,priority,predicate&id=in.({})",
+        playlist_ids.join(",")
+    ))?;
+    let playing: BTreeSet<&str> = playing(rows(&playlists), at)
+        .into_iter()
+        .filter_map(|playlist| playlist["id"].as_str())
+        .collect();
+
+    let rows = rows(&playlists)
+        .map(|playlist| {
+            let id = playlist["id"].as_str().unwrap_or_default();
+            let labels: Vec<&str> = playlist_labels
+                .get(id)
+                .into_iter()
+                .flatten()
+                .copied()
+                .collect();
+            json!({
+                "id": playlist["id"],
+                "title": playlist["title"],
+                "labels": labels.join(", "),
+                "is_enabled": playlist["is_enabled"],
+                "priority": playlist["priority"],
+                "predicate": playlist["predicate"],
+                "scheduled": is_scheduled(playlist, at),
+                "playing": playing.contains(id),
+            })
+        })
+        .collect();
+    Ok(ScreenPlaylists::new(serde_json::Value::Array(rows)))
+}
+
+/// Lists the intervals during which `predicate` holds between the start of `from` and the
+/// end of `to` in `timezone`, as the player would evaluate it.
+pub fn simulate(
+    predicate: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+    timezone: Timezone,
+    summary: &str,
+) -> Result<OnAirIntervals, CommandError> {
+    if to < from {
+        return Err(CommandError::InvalidArgument(format!(
+            "the range ends on {to}, before it starts on {from}"
+        )));
+    }
+    if (to - from).num_days() >= MAX_SIMULATED_DAYS {
+        return Err(CommandError::InvalidArgument(format!(
+            "the range spans more than {MAX_SIMULATED_DAYS} days"
+        )));
+    }
+    let expr = predicate::parse(predicate)?;
+    let intervals = on_air(&expr, from, to);
+    if let Some(odd) = sub_minute_switch(&intervals) {
+        warn!(
+            "The predicate switches at {}, $TIME counts milliseconds since midnight, not seconds.",
+            odd.format("%H:%M:%S%.3f")
+        );
+    }
+
+    let rows: Vec<serde_json::Value> = intervals
+        .into_iter()
+        .filter_map(|(start, end)| {
+            let start = localize(start, timezone.0);
+            let end = localize(end, timezone.0);
+            // Intervals that fall entirely into a skipped hour never go on air.
+            (start < end).then(|| {
+                json!({
+                    "start": start.to_rfc3339(),
+                    "end": end.to_rfc3339(),
+                    "duration": (end - start).num_seconds(),
+                })
+            })
+        })
+        .collect();
+    if rows.is_empty() {
+        warn!("The predicate is never true between {from} and {to}, nothing would be on air.");
+    }
+    Ok(OnAirIntervals::new(
+        serde_json::Value::Array(rows),
+        summary.to_string(),
+    ))
+}
+
+/// The first time of day at which the intervals start or end off a whole minute.
+///
+/// Schedules are set in minutes, so anything finer is most likely `$TIME` in seconds.
+/// Inclusive bounds such as `BETWEEN` end a millisecond after the minute, which is fine.
+fn sub_minute_switch(intervals: &[(NaiveDateTime, NaiveDateTime)]) -> Option<NaiveTime> {
+    let on_minute = |time: NaiveTime| time.second() == 0 && time.nanosecond() == 0;
+    intervals
+        .iter()
+        .flat_map(|(start, end)| [start.time(), end.time()])
+        .find(|time| !on_minute(*time) && !on_minute(*time - Duration::milliseconds(1)))
+}
+
+/// Wall-clock intervals, end excluded, during which `expr` holds from `from` to `to`.
+///
+/// Predicates only change value at midnight or where they compare `$TIME` with a literal,
+/// so evaluating them at those times of each day is enough.
+fn on_air(expr: &Expr, from: NaiveDate, to: NaiveDate) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+    let changes = expr.time_changes();
+    let mut intervals: Vec<(NaiveDateTime, NaiveDateTime)> = Vec::new();
+    for date in from.iter_days().take_while(|date| *date <= to) {
+        let midnight = date.and_time(NaiveTime::MIN);
+        for (index, time) in changes.iter().enumerate() {
+            let start = midnight + Duration::milliseconds(*time);
+            if !expr.evaluate(start) {
+                continue;
+            }
+            let end = midnight + Duration::milliseconds(*changes.get(index + 1).unwrap_or(&DAY_MS));
+            match intervals.last_mut() {
+                Some(last) if last.1 == start => last.1 = end,
+                _ => intervals.push((start, end)),
+            }
+        }
+    }
+    intervals
+}
+
+/// The instant a wall-clock time happens in `timezone`. Times repeated when the clocks go
+/// back resolve to their first occurrence, and times skipped when they go forward to the
+/// moment the clocks jump to.
+fn localize(at: NaiveDateTime, timezone: Tz) -> DateTime<Tz> {
+    let mut local = at;
+    loop {
+        if let Some(instant) = timezone.from_local_datetime(&local).earliest() {
+            return instant;
+        }
+        local += Duration::minutes(1);
+    }
+}
+
+fn rows(value: &serde_json::Value) -> impl Iterator<Item = &serde_json::Value> {
+    value.as_array().into_iter().flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use httpmock::Method::GET;
+    use httpmock::MockServer;
+
+    use super::*;
+    use crate::authentication::Config;
+
+    #[test]
+    fn test_local_time_should_fall_back_to_utc() {
+        let at = Utc.with_ymd_and_hms(2026, 10, 19, 7, 0, 0).unwrap();
+
+        let moscow = local_time(&json!("Europe/Moscow"), at);
+        assert_eq!(moscow.format("%H:%M").to_string(), "10:00");
+        assert_eq!(local_time(&json!("Mars/Olympus"), at), at.naive_utc());
+        assert_eq!(local_time(&json!(null), at), at.naive_utc());
+    }
+
+    #[test]
+    fn test_playing_should_prefer_scheduled_priority_playlists() {
+        let at = Utc
+            .with_ymd_and_hms(2026, 10, 19, 10, 0, 0)
+            .unwrap()
+            .naive_utc();
+        let playlists = json!([
+            {"id": "always", "is_enabled": true, "priority": false, "predicate": "TRUE"},
+            {"id": "evenings", "is_enabled": true, "priority": true, "predicate": "$TIME >= 64800000"},
+            {"id": "disabled", "is_enabled": false, "priority": true, "predicate": "TRUE"},
+        ]);
+        let ids = |playing: Vec<&serde_json::Value>| -> Vec<String> {
+            playing
+                .iter()
+                .map(|p| p["id"].as_str().unwrap().to_string())
+                .collect()
+        };
+
+        assert_eq!(ids(playing(rows(&playlists), at)), vec!["always"]);
+
+        let evening = at + chrono::Duration::hours(9);
+        assert_eq!(ids(playing(rows(&playlists), evening)), vec!["evenings"]);
+    }
+
+    #[test]
+    fn test_screen_playlists_should_evaluate_predicates_in_screen_timezone() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/screens")
+                .query_param("id", "eq.screen-1");
+            then.status(200)
+                .json_body(json!([{"id": "screen-1", "timezone": "Asia/Tokyo"}]));
+        });
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/labels/screens")
+                .query_param("screen_id", "eq.screen-1");
+            then.status(200)
+                .json_body(json!([{"label_id": "lobby"}, {"label_id": "north"}]));
+        });
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/labels/playlists")
+                .query_param("label_id", "in.(lobby,north)");
+            then.status(200).json_body(json!([
+                {"label_id": "lobby", "playlist_id": "breakfast"},
+                {"label_id": "north", "playlist_id": "breakfast"},
+                {"label_id": "north", "playlist_id": "dinner"},
+            ]));
+        });
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/labels")
+                .query_param("id", "in.(lobby,north)");
+            then.status(200).json_body(json!([
+                {"id": "lobby", "name": "Lobby"},
+                {"id": "north", "name": "North"},
+            ]));
+        });
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/playlists")
+                .query_param("id", "in.(breakfast,dinner)");
+            then.status(200).json_body(json!([
+                {"id": "breakfast", "title": "Breakfast", "is_enabled": true, "priority": false, "predicate": "$TIME < 39600000"},
+                {"id": "dinner", "title": "Dinner", "is_enabled": true, "priority": false, "predicate": "$TIME >= 61200000"},
+            ]));
+        });
+
+        let config = Config::new(mock_server.base_url());
+        let authentication = Authentication::new_with_config(config, "token");
+        // 00:30 UTC is 09:30 in Tokyo.
+        let at = Utc.with_ymd_and_hms(2026, 10, 19, 0, 30, 0).unwrap();
+        let playlists = screen_playlists(&authentication, "screen-1", at).unwrap();
+
+        assert_eq!(playlists.value[0]["labels"], "Lobby, North");
+        assert_eq!(playlists.value[0]["playing"], true);
+        assert_eq!(playlists.value[1]["labels"], "North");
+        assert_eq!(playlists.value[1]["scheduled"], false);
+        assert_eq!(playlists.value[1]["playing"], false);
+    }
+
+    #[test]
+    fn test_simulate_should_list_business_hours_in_timezone() {
+        let from = NaiveDate::from_ymd_opt(2026, 10, 23).unwrap();
+        let to = NaiveDate::from_ymd_opt(2026, 10, 27).unwrap();
+        let timezone: Timezone = "Europe/London".parse().unwrap();
+        let intervals = simulate(
+            "$WEEKDAY BETWEEN {1, 5} AND $TIME BETWEEN {32400000, 61199999}",
+            from,
+            to,
+            timezone,
+            "Office",
+        )
+        .unwrap();
+
+        let starts: Vec<&str> = rows(&intervals.value)
+            .map(|interval| interval["start"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            starts,
+            vec![
+                "2026-10-23T09:00:00+01:00",
+                "2026-10-26T09:00:00+00:00",
+                "2026-10-27T09:00:00+00:00",
+            ]
+        );
+        assert_eq!(intervals.value[0]["end"], "2026-10-23T17:00:00+01:00");
+        assert_eq!(intervals.value[0]["duration"], 8 * 3600);
+
+        assert!(matches!(
+            simulate("TRUE", to, from, timezone, "Office"),
+            Err(CommandError::InvalidArgument(_))
+        ));
+        assert!("Mars/Olympus".parse::<Timezone>().is_err());
+    }
+
+    #[test]
+    fn test_sub_minute_switch_should_ignore_inclusive_ends() {
+        let day = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let switch = |predicate: &str| {
+            sub_minute_switch(&on_air(&predicate::parse(predicate).unwrap(), day, day))
+        };
+
+        assert_eq!(switch("$TIME BETWEEN {32400000, 61200000}"), None);
+        assert_eq!(switch("$TIME >= 32400000 AND $TIME <= 61200000"), None);
+        assert_eq!(
+            switch("$TIME BETWEEN {32400, 61200}"),
+            NaiveTime::from_hms_milli_opt(0, 0, 32, 400)
+        );
+    }
+
+    #[test]
+    fn test_simulate_should_merge_days_across_dst_change() {
+        let from = NaiveDate::from_ymd_opt(2026, 10, 24).unwrap();
+        let to = NaiveDate::from_ymd_opt(2026, 10, 26).unwrap();
+        let timezone: Timezone = "Europe/London".parse().unwrap();
+        let intervals = simulate("TRUE", from, to, timezone, "Lobby, main").unwrap();
+
+        // The clocks go back on October 25th, so the three days last 73 hours.
+        assert_eq!(
+            intervals.value,
+            json!([{
+                "start": "2026-10-24T00:00:00+01:00",
+                "end": "2026-10-27T00:00:00+00:00",
+                "duration": 73 * 3600,
+            }])
+        );
+
+        let ical = intervals.ical();
+        assert!(ical.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ical.contains("\r\nDTSTART:20261023T230000Z\r\nDTEND:20261027T000000Z\r\n"));
+        assert!(ical.contains("\r\nSUMMARY:Lobby\\, main\r\n"));
+        assert!(ical.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+    }
+}
@@ -1,10 +1,124 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::str::FromStr;
 
-use reqwest::StatusCode;
+use chrono::Utc;
+use reqwest::{Method, StatusCode};
+use serde::Serialize;
+use serde_json::json;
 
 use crate::authentication::Authentication;
 use crate::commands;
-use crate::commands::{CommandError, Screens};
+use crate::commands::health;
+use crate::commands::inventory::{self, InventoryRow};
+use crate::commands::label;
+use crate::commands::lookup::{self, Resource};
+use crate::commands::pagination::{self, Pages, Pagination};
+use crate::commands::retry::send_with_retry;
+use crate::commands::schedule;
+use crate::commands::{
+    ApiError, CommandError, ImportReport, ScreenPlaylists, ScreenStatuses, Screens,
+};
+
+// Keeps `id=in.(...)` filters well below common URL length limits.
+const SCREEN_IDS_PER_REQUEST: usize = 100;
+
+/// Latitude and longitude of a screen in degrees, written as `lat,lng`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Coordinates {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl FromStr for Coordinates {
+    type Err = CommandError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            CommandError::InvalidArgument(format!(
+                "coordinates must be \"latitude,longitude\" in degrees, got \"{s}\""
+            ))
+        };
+        let (latitude, longitude) = s.split_once(',').ok_or_else(invalid)?;
+        let latitude: f64 = latitude.trim().parse().map_err(|_| invalid())?;
+        let longitude: f64 = longitude.trim().parse().map_err(|_| invalid())?;
+        if !(-90.0..=90.0).contains(&latitude) {
+            return Err(CommandError::InvalidArgument(format!(
+                "latitude {latitude} is outside of -90..90"
+            )));
+        }
+        if !(-180.0..=180.0).contains(&longitude) {
+            return Err(CommandError::InvalidArgument(format!(
+                "longitude {longitude} is outside of -180..180"
+            )));
+        }
+        Ok(Self {
+            latitude,
+            longitude,
+        })
+    }
+}
+
+// Parsed coordinates are never NaN, so they are totally ordered.
+impl Eq for Coordinates {}
+
+impl PartialOrd for Coordinates {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Coordinates {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.latitude
+            .total_cmp(&other.latitude)
+            .then(self.longitude.total_cmp(&other.longitude))
+    }
+}
+
+impl Serialize for Coordinates {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        [self.latitude, self.longitude].serialize(serializer)
+    }
+}
+
+/// Fields changed by `screen update`. Fields left as `None` are not sent.
+#[derive(Debug, Default, Serialize)]
+pub struct ScreenUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(rename = "coords", skip_serializing_if = "Option::is_none")]
+    pub coordinates: Option<Coordinates>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+impl ScreenUpdate {
+    fn validate(&self) -> Result<(), CommandError> {
+        if self.name.is_none()
+            && self.location.is_none()
+            && self.coordinates.is_none()
+            && self.notes.is_none()
+        {
+            return Err(CommandError::InvalidArgument(
+                "nothing to update, pass at least one of --name, --location, --coordinates or --notes"
+                    .to_string(),
+            ));
+        }
+        if self
+            .name
+            .as_ref()
+            .is_some_and(|name| name.trim().is_empty())
+        {
+            return Err(CommandError::InvalidArgument(
+                "screen name cannot be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
 
 pub struct ScreenCommand {
     authentication: Authentication,
@@ -15,13 +129,85 @@ impl ScreenCommand {
         Self { authentication }
     }
 
-    pub fn list(&self) -> anyhow::Result<Screens, CommandError> {
-        Ok(Screens::new(commands::get(
+    pub fn list(&self, pagination: Pagination) -> Result<Screens, CommandError> {
+        Ok(Screens::new(pagination::get_all(
             &self.authentication,
             "v4/screens",
+            pagination,
         )?))
     }
 
+    /// The rows of [`Self::list`], one page at a time.
+    pub fn pages(&self, pagination: Pagination) -> Pages<'_> {
+        Pages::new(&self.authentication, "v4/screens", pagination)
+    }
+
+    /// Registers the screens of an inventory, see [`inventory::import`].
+    pub fn import(&self, rows: &[InventoryRow], jobs: usize) -> Result<ImportReport, CommandError> {
+        inventory::import(&self.authentication, rows, jobs)
+    }
+
+    /// Lists the screens that carry a label, given by id, name or `name:<glob>` selector.
+    pub fn list_with_label(
+        &self,
+        label: &str,
+        pagination: Pagination,
+    ) -> Result<Screens, CommandError> {
+        let label_id = lookup::resolve(&self.authentication, Resource::Label, label)?;
+        let ids = label::screen_ids(&self.authentication, &label_id)?;
+        // The ids go into the URL, so a label on many screens takes several requests.
+        let mut screens = Vec::new();
+        for chunk in ids.chunks(SCREEN_IDS_PER_REQUEST) {
+            let limit = pagination.limit.map(|limit| limit - screens.len());
+            if limit == Some(0) {
+                break;
+            }
+            let rows = pagination::get_all(
+                &self.authentication,
+                &format!("v4/screens?id=in.({})", chunk.join(",")),
+                Pagination {
+                    limit,
+                    ..pagination
+                },
+            )?;
+            if let serde_json::Value::Array(rows) = rows {
+                screens.extend(rows);
+            }
+        }
+        Ok(Screens::new(serde_json::Value::Array(screens)))
+    }
+
+    /// Summarises the health of all screens, or of the screens that carry `label`.
+    pub fn status(&self, label: Option<&str>) -> Result<ScreenStatuses, CommandError> {
+        let screens = match label {
+            Some(label) => self.list_with_label(label, Pagination::default())?,
+            None => self.list(Pagination::default())?,
+        };
+        health::screen_statuses(&self.authentication, &screens.value)
+    }
+
+    /// Lists the playlists that reach the screen through its labels, only those that should
+    /// be playing right now when `playing_only` is set.
+    pub fn playlists(&self, id: &str, playing_only: bool) -> Result<ScreenPlaylists, CommandError> {
+        let mut playlists = schedule::screen_playlists(&self.authentication, id, Utc::now())?;
+        if playing_only {
+            if let Some(rows) = playlists.value.as_array_mut() {
+                rows.retain(|playlist| playlist["playing"] == true);
+            }
+        }
+        Ok(playlists)
+    }
+
+    /// Resolves a screen id, name or `name:<glob>` selector to an id.
+    pub fn resolve(&self, reference: &str) -> Result<String, CommandError> {
+        lookup::resolve(&self.authentication, Resource::Screen, reference)
+    }
+
+    /// Looks up an id-shaped reference as a screen name, for when nothing has that id.
+    pub fn resolve_id_as_name(&self, reference: &str) -> Result<Option<String>, CommandError> {
+        lookup::resolve_id_as_name(&self.authentication, Resource::Screen, reference)
+    }
+
     pub fn get(&self, id: &str) -> anyhow::Result<Screens, CommandError> {
         let endpoint = format!("v4/screens?id=eq.{id}");
 
@@ -36,28 +222,11 @@ impl ScreenCommand {
         pin: &str,
         maybe_name: Option<String>,
     ) -> anyhow::Result<Screens, CommandError> {
-        let url = format!("{}/v3/screens/", &self.authentication.config.url);
-        let mut payload = HashMap::new();
-        payload.insert("pin".to_string(), pin.to_string());
-        if let Some(name) = maybe_name {
-            payload.insert("name".to_string(), name);
-        }
-        let response = self
-            .authentication
-            .build_client()?
-            .post(url)
-            .json(&payload)
-            .send()?;
-        if response.status() != StatusCode::CREATED {
-            return Err(CommandError::WrongResponseStatus(
-                response.status().as_u16(),
-            ));
-        }
+        add(&self.authentication, pin, maybe_name)
+    }
 
-        // Our newer endpoints all return arrays so let's just convert the output from v3 to be the same
-        let mut array: Vec<serde_json::Value> = Vec::new();
-        array.insert(0, serde_json::from_str(&response.text()?)?);
-        Ok(Screens::new(serde_json::Value::Array(array)))
+    pub fn update(&self, id: &str, changes: &ScreenUpdate) -> Result<Screens, CommandError> {
+        update(&self.authentication, id, changes)
     }
 
     pub fn delete(&self, id: &str) -> anyhow::Result<(), CommandError> {
@@ -66,16 +235,64 @@ impl ScreenCommand {
     }
 }
 
+/// Registers a screen with the pin shown on the player.
+pub fn add(
+    authentication: &Authentication,
+    pin: &str,
+    maybe_name: Option<String>,
+) -> anyhow::Result<Screens, CommandError> {
+    let url = format!("{}/v3/screens/", authentication.config.url);
+    let mut payload = HashMap::new();
+    payload.insert("pin".to_string(), pin.to_string());
+    if let Some(name) = maybe_name {
+        payload.insert("name".to_string(), name);
+    }
+    if authentication.config.dry_run {
+        let planned = commands::dry_run(Method::POST, &url, Some(json!(payload)))?;
+        return Ok(Screens::new(planned));
+    }
+
+    let client = authentication.build_client()?;
+    let response = send_with_retry(authentication, false, || {
+        Ok(client.post(&url).json(&payload))
+    })?;
+    if response.status() != StatusCode::CREATED {
+        return Err(ApiError::from_response(Method::POST, response).into());
+    }
+
+    // Our newer endpoints all return arrays so let's just convert the output from v3 to be the same
+    let mut array: Vec<serde_json::Value> = Vec::new();
+    array.insert(0, serde_json::from_str(&response.text()?)?);
+    Ok(Screens::new(serde_json::Value::Array(array)))
+}
+
+pub fn update(
+    authentication: &Authentication,
+    id: &str,
+    changes: &ScreenUpdate,
+) -> Result<Screens, CommandError> {
+    changes.validate()?;
+    let endpoint = format!("v4/screens?id=eq.{id}");
+    let value = commands::patch(authentication, &endpoint, changes)?;
+    if value.as_array().is_some_and(|screens| screens.is_empty()) {
+        return Err(CommandError::ResourceNotFound(
+            "screen".to_string(),
+            id.to_string(),
+        ));
+    }
+    Ok(Screens::new(value))
+}
+
 #[cfg(test)]
 mod tests {
-    use httpmock::Method::{DELETE, GET, POST};
+    use httpmock::Method::{DELETE, GET, PATCH, POST};
     use httpmock::MockServer;
     use serde_json::{json, Value};
     use tempfile::tempdir;
 
     use super::*;
     use crate::authentication::{Authentication, Config};
-    use crate::commands::{Formatter, OutputType};
+    use crate::commands::{FormatOptions, Formatter, OutputType};
 
     #[test]
     fn test_list_screens_should_return_correct_screen_list() {
@@ -98,10 +315,107 @@ mod tests {
         let config = Config::new(mock_server.base_url());
         let authentication = Authentication::new_with_config(config, "token");
         let screen_command = ScreenCommand::new(authentication);
-        let v = screen_command.list().unwrap();
+        let v = screen_command.list(Pagination::default()).unwrap();
         assert_eq!(v.value, screens);
     }
 
+    #[test]
+    fn test_list_screens_with_label_should_only_fetch_labelled_screens() {
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/labels")
+                .query_param("name", "eq.Lobby");
+            then.status(200)
+                .json_body(json!([{"id": "01H2QZ6Z8WXWNDC0KQ198XCZEL", "name": "Lobby"}]));
+        });
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/labels/screens")
+                .query_param("label_id", "eq.01H2QZ6Z8WXWNDC0KQ198XCZEL");
+            then.status(200)
+                .json_body(json!([{"screen_id": "screen-1"}, {"screen_id": "screen-2"}]));
+        });
+        let screens_mock = mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/screens")
+                .query_param("id", "in.(screen-1,screen-2)");
+            then.status(200)
+                .json_body(json!([{"id": "screen-1"}, {"id": "screen-2"}]));
+        });
+
+        let config = Config::new(mock_server.base_url());
+        let authentication = Authentication::new_with_config(config, "token");
+        let screen_command = ScreenCommand::new(authentication);
+        let screens = screen_command
+            .list_with_label("Lobby", Pagination::default())
+            .unwrap();
+        screens_mock.assert();
+        assert_eq!(
+            screens.value,
+            json!([{"id": "screen-1"}, {"id": "screen-2"}])
+        );
+    }
+
+    #[test]
+    fn test_list_screens_with_label_on_many_screens_should_split_ids() {
+        let ids: Vec<String> = (0..150).map(|i| format!("screen-{i}")).collect();
+        let mock_server = MockServer::start();
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/labels")
+                .query_param("name", "eq.Lobby");
+            then.status(200)
+                .json_body(json!([{"id": "01H2QZ6Z8WXWNDC0KQ198XCZEL", "name": "Lobby"}]));
+        });
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/labels/screens")
+                .query_param("label_id", "eq.01H2QZ6Z8WXWNDC0KQ198XCZEL");
+            then.status(200).json_body(json!(ids
+                .iter()
+                .map(|id| json!({ "screen_id": id }))
+                .collect::<Vec<_>>()));
+        });
+        let mocks: Vec<_> = ids
+            .chunks(100)
+            .map(|chunk| {
+                mock_server.mock(|when, then| {
+                    when.method(GET)
+                        .path("/v4/screens")
+                        .query_param("id", format!("in.({})", chunk.join(",")));
+                    then.status(200).json_body(json!(chunk
+                        .iter()
+                        .map(|id| json!({ "id": id }))
+                        .collect::<Vec<_>>()));
+                })
+            })
+            .collect();
+
+        let config = Config::new(mock_server.base_url());
+        let authentication = Authentication::new_with_config(config, "token");
+        let screen_command = ScreenCommand::new(authentication);
+        let screens = screen_command
+            .list_with_label("Lobby", Pagination::default())
+            .unwrap();
+        for mock in &mocks {
+            mock.assert();
+        }
+        assert_eq!(screens.value.as_array().unwrap().len(), 150);
+
+        let screens = screen_command
+            .list_with_label(
+                "Lobby",
+                Pagination {
+                    limit: Some(50),
+                    ..Pagination::default()
+                },
+            )
+            .unwrap();
+        mocks[1].assert_calls(1);
+        assert_eq!(screens.value.as_array().unwrap().len(), 50);
+    }
+
     #[test]
     fn test_add_screen_should_send_correct_request() {
         let new_screen = serde_json::from_str::<Value>("{\"id\":\"017a5104-524b-33d8-8026-9087b59e7eb5\",\"team_id\":\"016343c2-82b8-0000-a121-e30f1035875e\",\"created_at\":\"2021-06-28T05:07:55+00:00\",\"name\":\"Test\",\"is_enabled\":true,\"coords\":[55.22931, 48.90429],\"last_ping\":\"2021-08-25T06:17:20.728+00:00\",\"last_ip\":null,\"local_ip\":\"192.168.1.146\",\"mac\":\"b8:27:eb:d6:83:6f\",\"last_screenshot_time\":\"2021-08-25T06:09:04.399+00:00\",\"uptime\":\"230728.38\",\"load_avg\":\"0.14\",\"signal_strength\":null,\"interface\":\"eth0\",\"debug\":false,\"location\":\"Kamsko-Ust'inskiy rayon, Russia\",\"team\":\"016343c2-82b8-0000-a121-e30f1035875e\",\"timezone\":\"Europe/Moscow\",\"type\":\"hardware\",\"hostname\":\"srly-4shnfrdc5cd2p0p\",\"ws_open\":false,\"status\":\"Offline\",\"last_screenshot\":\"https://us-assets.screenlyapp.com/01CD1W50NR000A28F31W83B1TY/screenshots/01F98G8MJB6FC809MGGYTSWZNN/5267668e6db35498e61b83d4c702dbe8\",\"in_sync\":false,\"software_version\":\"Screenly 2 Player\",\"hardware_version\":\"Raspberry Pi 3B\",\"config\":{\"hdmi_mode\": 34, \"hdmi_boost\": 2, \"hdmi_drive\": 0, \"hdmi_group\": 0, \"verify_ssl\": true, \"audio_output\": \"hdmi\", \"hdmi_timings\": \"\", \"overscan_top\": 0, \"overscan_left\": 0, \"use_composite\": false, \"display_rotate\": 0, \"overscan_right\": 0, \"overscan_scale\": 0, \"overscan_bottom\": 0, \"disable_overscan\": 0, \"shuffle_playlist\": false, \"framebuffer_width\": 0, \"use_composite_pal\": false, \"framebuffer_height\": 0, \"hdmi_force_hotplug\": true, \"use_composite_ntsc\": false, \"hdmi_pixel_encoding\": 0, \"play_history_enabled\": false}}").unwrap();
@@ -172,11 +486,93 @@ mod tests {
         assert!(screen_command.delete("test-id").is_ok());
     }
 
+    #[test]
+    fn test_update_screen_should_patch_given_fields() {
+        let mock_server = MockServer::start();
+        let mut patch_mock = mock_server.mock(|when, then| {
+            when.method(PATCH)
+                .path("/v4/screens")
+                .query_param("id", "eq.017a5104-524b-33d8-8026-9087b59e7eb5")
+                .header("Authorization", "Token token")
+                .header("Prefer", "return=representation")
+                .json_body(json!({"name": "Lobby", "coords": [59.33, -18.07]}));
+            then.status(200).json_body(json!([{
+                "id": "017a5104-524b-33d8-8026-9087b59e7eb5",
+                "name": "Lobby",
+                "coords": [59.33, -18.07]
+            }]));
+        });
+
+        let config = Config::new(mock_server.base_url());
+        let authentication = Authentication::new_with_config(config, "token");
+        let screen_command = ScreenCommand::new(authentication);
+        let update = ScreenUpdate {
+            name: Some("Lobby".to_string()),
+            coordinates: Some("59.33, -18.07".parse().unwrap()),
+            ..Default::default()
+        };
+        let screens = screen_command
+            .update("017a5104-524b-33d8-8026-9087b59e7eb5", &update)
+            .unwrap();
+        patch_mock.assert();
+        assert_eq!(screens.value[0]["name"], "Lobby");
+
+        patch_mock.delete();
+        mock_server.mock(|when, then| {
+            when.method(PATCH).path("/v4/screens");
+            then.status(200).json_body(json!([]));
+        });
+        let error = screen_command.update("missing", &update).unwrap_err();
+        assert_eq!(error.to_string(), "No screen matches \"missing\".");
+    }
+
+    #[test]
+    fn test_update_screen_should_reject_invalid_input() {
+        let mock_server = MockServer::start();
+        let patch_mock = mock_server.mock(|when, then| {
+            when.method(PATCH);
+            then.status(200).json_body(json!([]));
+        });
+        let config = Config::new(mock_server.base_url());
+        let screen_command = ScreenCommand::new(Authentication::new_with_config(config, "token"));
+
+        assert!(matches!(
+            screen_command.update("id", &ScreenUpdate::default()),
+            Err(CommandError::InvalidArgument(_))
+        ));
+        let blank_name = ScreenUpdate {
+            name: Some("  ".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            screen_command.update("id", &blank_name),
+            Err(CommandError::InvalidArgument(_))
+        ));
+        patch_mock.assert_calls(0);
+
+        assert!("91,10".parse::<Coordinates>().is_err());
+        assert!("10,-180.5".parse::<Coordinates>().is_err());
+        assert!("10".parse::<Coordinates>().is_err());
+        assert!("north,east".parse::<Coordinates>().is_err());
+        assert_eq!(
+            "-33.87,151.21".parse::<Coordinates>().unwrap(),
+            Coordinates {
+                latitude: -33.87,
+                longitude: 151.21
+            }
+        );
+    }
+
     #[test]
     fn test_format_screen_when_human_readable_output_is_set_should_return_correct_formatted_string()
     {
         let screen = Screens::new(serde_json::from_str("[{\"id\":\"017a5104-524b-33d8-8026-9087b59e7eb5\",\"team_id\":\"016343c2-82b8-0000-a121-e30f1035875e\",\"created_at\":\"2021-06-28T05:07:55+00:00\",\"name\":\"Renat's integrated wired NM\",\"is_enabled\":true,\"coords\":[55.22931, 48.90429],\"last_ping\":\"2021-08-25T06:17:20.728+00:00\",\"last_ip\":null,\"local_ip\":\"192.168.1.146\",\"mac\":\"b8:27:eb:d6:83:6f\",\"last_screenshot_time\":\"2021-08-25T06:09:04.399+00:00\",\"uptime\": 230728,\"load_avg\":\"0.14\",\"signal_strength\":null,\"interface\":\"eth0\",\"debug\":false,\"location\":\"Kamsko-Ust'inskiy rayon, Russia\",\"team\":\"016343c2-82b8-0000-a121-e30f1035875e\",\"timezone\":\"Europe/Moscow\",\"type\":\"hardware\",\"hostname\":\"srly-4shnfrdc5cd2p0p\",\"ws_open\":false,\"status\":\"Offline\",\"last_screenshot\":\"https://us-assets.screenlyapp.com/01CD1W50NR000A28F31W83B1TY/screenshots/01F98G8MJB6FC809MGGYTSWZNN/5267668e6db35498e61b83d4c702dbe8\",\"in_sync\":false,\"software_version\":\"Screenly 2 Player\",\"hardware_version\":\"Raspberry Pi 3B\",\"config\":{\"hdmi_mode\": 34, \"hdmi_boost\": 2, \"hdmi_drive\": 0, \"hdmi_group\": 0, \"verify_ssl\": true, \"audio_output\": \"hdmi\", \"hdmi_timings\": \"\", \"overscan_top\": 0, \"overscan_left\": 0, \"use_composite\": false, \"display_rotate\": 0, \"overscan_right\": 0, \"overscan_scale\": 0, \"overscan_bottom\": 0, \"disable_overscan\": 0, \"shuffle_playlist\": false, \"framebuffer_width\": 0, \"use_composite_pal\": false, \"framebuffer_height\": 0, \"hdmi_force_hotplug\": true, \"use_composite_ntsc\": false, \"hdmi_pixel_encoding\": 0, \"play_history_enabled\": false}}, {\"id\":\"017a5104-524b-33d8-8026-9087b59e7eb6\",\"team_id\":\"016343c2-82b8-0000-a121-e30f1035875d\",\"created_at\":\"2020-06-28T05:07:55+00:00\",\"name\":\"Not Renat's integrated wired NM\",\"is_enabled\":true,\"coords\":[55.22931, 48.90429],\"last_ping\":\"2020-08-25T06:17:20.728+00:00\",\"last_ip\":null,\"local_ip\":\"192.168.1.146\",\"mac\":\"b8:27:eb:d6:83:6f\",\"last_screenshot_time\":\"2021-08-25T06:09:04.399+00:00\",\"uptime\":230728,\"load_avg\":\"0.14\",\"signal_strength\":null,\"interface\":\"eth0\",\"debug\":false,\"location\":\"Kamsko-Ust'inskiy rayon, Russia\",\"team\":\"016343c2-82b8-0000-a121-e30f1035875e\",\"timezone\":\"Europe/Moscow\",\"type\":\"hardware\",\"hostname\":\"srly-4shnfrdc5cd2p0p\",\"ws_open\":false,\"status\":\"Offline\",\"last_screenshot\":\"https://us-assets.screenlyapp.com/01CD1W50NR000A28F31W83B1TY/screenshots/01F98G8MJB6FC809MGGYTSWZNN/5267668e6db35498e61b83d4c702dbe8\",\"in_sync\":false,\"software_version\":\"Screenly 2 Player\",\"hardware_version\":\"Raspberry Pi 3B\",\"config\":{\"hdmi_mode\": 34, \"hdmi_boost\": 2, \"hdmi_drive\": 0, \"hdmi_group\": 0, \"verify_ssl\": true, \"audio_output\": \"hdmi\", \"hdmi_timings\": \"\", \"overscan_top\": 0, \"overscan_left\": 0, \"use_composite\": false, \"display_rotate\": 0, \"overscan_right\": 0, \"overscan_scale\": 0, \"overscan_bottom\": 0, \"disable_overscan\": 0, \"shuffle_playlist\": false, \"framebuffer_width\": 0, \"use_composite_pal\": false, \"framebuffer_height\": 0, \"hdmi_force_hotplug\": true, \"use_composite_ntsc\": false, \"hdmi_pixel_encoding\": 0, \"play_history_enabled\": false}}]").unwrap());
-        println!("{}", screen.format(OutputType::HumanReadable));
+        println!(
+            "{}",
+            screen
+                .format_with(&FormatOptions::new(OutputType::HumanReadable))
+                .unwrap()
+        );
         let expected_output =
             "+--------------------------------------+---------------------------------+---------+----------+------------------+---------+-------------------------------+--------+\n\
 | Id                                   | Name                            | Enabled | Priority | Hardware Version | In Sync | Last Ping                     | Uptime |\n\
@@ -186,6 +582,11 @@ mod tests {
 | 017a5104-524b-33d8-8026-9087b59e7eb6 | Not Renat's integrated wired NM |   ✅    |    ❌    | Raspberry Pi 3B  |   ❌    | 2020-08-25T06:17:20.728+00:00 | 3 days |\n\
 +--------------------------------------+---------------------------------+---------+----------+------------------+---------+-------------------------------+--------+\n";
 
-        assert_eq!(screen.format(OutputType::HumanReadable), expected_output);
+        assert_eq!(
+            screen
+                .format_with(&FormatOptions::new(OutputType::HumanReadable))
+                .unwrap(),
+            expected_output
+        );
     }
 }
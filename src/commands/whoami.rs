@@ -0,0 +1,245 @@
+use chrono::{DateTime, Duration, Utc};
+use serde_json::json;
+
+use crate::authentication::Authentication;
+use crate::commands;
+use crate::commands::{CommandError, Whoami};
+
+pub const TOKEN_VALID: &str = "valid";
+pub const TOKEN_EXPIRING: &str = "expiring";
+pub const TOKEN_EXPIRED: &str = "expired";
+pub const TOKEN_REJECTED: &str = "rejected";
+
+pub struct WhoamiCommand {
+    authentication: Authentication,
+}
+
+impl WhoamiCommand {
+    pub fn new(authentication: Authentication) -> Self {
+        Self { authentication }
+    }
+
+    /// Describes the user, team and token behind the active credentials.
+    /// Tokens expiring within `warn_within` are reported as `expiring`.
+    pub fn whoami(&self, warn_within: Duration) -> Result<Whoami, CommandError> {
+        let user = match commands::get(&self.authentication, "v4/users/me") {
+            Ok(user) => first(user),
+            Err(e) if matches!(e.status(), Some(401 | 403)) => {
+                return Ok(Whoami::new(json!([self.describe(
+                    &serde_json::Value::Null,
+                    &serde_json::Value::Null,
+                    &serde_json::Value::Null,
+                    TOKEN_REJECTED,
+                )])));
+            }
+            Err(e) => return Err(e),
+        };
+
+        let team = match user["team_id"].as_str() {
+            Some(team_id) => first(commands::get(
+                &self.authentication,
+                &format!("v4/teams?id=eq.{team_id}"),
+            )?),
+            None => serde_json::Value::Null,
+        };
+
+        // Older API deployments don't expose token details, which only leaves scope and
+        // expiry unknown.
+        let token = match commands::get(&self.authentication, "v4/tokens/me") {
+            Ok(token) => first(token),
+            Err(e) if e.status() == Some(404) => serde_json::Value::Null,
+            Err(e) => return Err(e),
+        };
+
+        let status = token_status(token["expires_at"].as_str(), Utc::now(), warn_within);
+        Ok(Whoami::new(json!([
+            self.describe(&user, &team, &token, status)
+        ])))
+    }
+
+    fn describe(
+        &self,
+        user: &serde_json::Value,
+        team: &serde_json::Value,
+        token: &serde_json::Value,
+        status: &str,
+    ) -> serde_json::Value {
+        let scope = match &token["scope"] {
+            serde_json::Value::Array(scopes) => json!(scopes
+                .iter()
+                .filter_map(|s| s.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")),
+            scope => scope.clone(),
+        };
+
+        json!({
+            "profile": self.authentication.config.profile,
+            "environment": self.authentication.config.environment().to_string(),
+            "api_url": self.authentication.config.url,
+            "account": user["email"],
+            "team": team["name"],
+            "team_id": user["team_id"],
+            "scope": scope,
+            "expires_at": token["expires_at"],
+            "status": status,
+        })
+    }
+}
+
+// PostgREST endpoints return arrays even when a single row matches.
+fn first(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Array(mut rows) if !rows.is_empty() => rows.swap_remove(0),
+        serde_json::Value::Array(_) => serde_json::Value::Null,
+        value => value,
+    }
+}
+
+fn token_status(
+    expires_at: Option<&str>,
+    now: DateTime<Utc>,
+    warn_within: Duration,
+) -> &'static str {
+    let Some(expires_at) = expires_at
+        .and_then(|e| DateTime::parse_from_rfc3339(e).ok())
+        .map(|e| e.with_timezone(&Utc))
+    else {
+        return TOKEN_VALID;
+    };
+
+    if expires_at <= now {
+        TOKEN_EXPIRED
+    } else if expires_at - now <= warn_within {
+        TOKEN_EXPIRING
+    } else {
+        TOKEN_VALID
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use httpmock::Method::GET;
+    use httpmock::MockServer;
+
+    use super::*;
+    use crate::authentication::Config;
+    use crate::commands::{FormatOptions, Formatter, OutputType};
+
+    fn mock_user_and_team(mock_server: &MockServer) {
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/users/me")
+                .header("Authorization", "Token token");
+            then.status(200).json_body(json!([{
+                "id": "01H2QZ6Z8WXWNDC0KQ198XCZEW",
+                "email": "jane@example.com",
+                "team_id": "01H2QZ6Z8WXWNDC0KQ198XCZEB"
+            }]));
+        });
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/v4/teams")
+                .query_param("id", "eq.01H2QZ6Z8WXWNDC0KQ198XCZEB");
+            then.status(200).json_body(json!([{
+                "id": "01H2QZ6Z8WXWNDC0KQ198XCZEB",
+                "name": "Acme"
+            }]));
+        });
+    }
+
+    #[test]
+    fn test_whoami_should_describe_user_team_and_token() {
+        let mock_server = MockServer::start();
+        mock_user_and_team(&mock_server);
+        mock_server.mock(|when, then| {
+            when.method(GET).path("/v4/tokens/me");
+            then.status(200).json_body(json!([{
+                "scope": ["read", "write"],
+                "expires_at": null
+            }]));
+        });
+
+        let config = Config::new(mock_server.base_url());
+        let whoami_command = WhoamiCommand::new(Authentication::new_with_config(config, "token"));
+        let whoami = whoami_command.whoami(Duration::days(7)).unwrap();
+
+        assert_eq!(
+            whoami.value,
+            json!([{
+                "profile": "default",
+                "environment": mock_server.base_url(),
+                "api_url": mock_server.base_url(),
+                "account": "jane@example.com",
+                "team": "Acme",
+                "team_id": "01H2QZ6Z8WXWNDC0KQ198XCZEB",
+                "scope": "read, write",
+                "expires_at": null,
+                "status": "valid",
+            }])
+        );
+        assert!(whoami
+            .format_with(&FormatOptions::new(OutputType::HumanReadable))
+            .unwrap()
+            .contains("jane@example.com"));
+    }
+
+    #[test]
+    fn test_whoami_when_token_endpoint_missing_should_leave_scope_unknown() {
+        let mock_server = MockServer::start();
+        mock_user_and_team(&mock_server);
+        mock_server.mock(|when, then| {
+            when.method(GET).path("/v4/tokens/me");
+            then.status(404);
+        });
+
+        let config = Config::new(mock_server.base_url());
+        let whoami_command = WhoamiCommand::new(Authentication::new_with_config(config, "token"));
+        let whoami = whoami_command.whoami(Duration::days(7)).unwrap();
+
+        assert_eq!(whoami.value[0]["account"], "jane@example.com");
+        assert!(whoami.value[0]["scope"].is_null());
+        assert_eq!(whoami.value[0]["status"], TOKEN_VALID);
+    }
+
+    #[test]
+    fn test_whoami_when_token_rejected_should_report_rejected_status() {
+        for status in [401, 403] {
+            let mock_server = MockServer::start();
+            mock_server.mock(|when, then| {
+                when.method(GET).path("/v4/users/me");
+                then.status(status);
+            });
+
+            let config = Config::new(mock_server.base_url());
+            let whoami_command =
+                WhoamiCommand::new(Authentication::new_with_config(config, "token"));
+            let whoami = whoami_command.whoami(Duration::days(7)).unwrap();
+
+            assert_eq!(whoami.value[0]["status"], TOKEN_REJECTED);
+            assert!(whoami.value[0]["account"].is_null());
+        }
+    }
+
+    #[test]
+    fn test_token_status_should_compare_expiry_with_warning_window() {
+        let now = DateTime::parse_from_rfc3339("2024-05-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let week = Duration::days(7);
+
+        assert_eq!(token_status(None, now, week), TOKEN_VALID);
+        assert_eq!(
+            token_status(Some("2024-06-01T00:00:00Z"), now, week),
+            TOKEN_VALID
+        );
+        assert_eq!(
+            token_status(Some("2024-05-03T00:00:00+00:00"), now, week),
+            TOKEN_EXPIRING
+        );
+        assert_eq!(
+            token_status(Some("2024-04-30T00:00:00Z"), now, week),
+            TOKEN_EXPIRED
+        );
+    }
+}
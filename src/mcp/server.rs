@@ -77,6 +77,9 @@ pub struct AssetGroupUpdateParam {
 /// - `$WEEKDAY IN {1, 2, 3, 4, 5}` - Weekdays only
 /// - `$TIME BETWEEN {32400000, 61200000}` - 9 AM to 5 PM
 /// - `$TIME >= 32400000 AND $TIME <= 61200000 AND NOT $WEEKDAY IN {0, 6}` - Business hours
+///
+/// The playlist tools check predicates with [`crate::commands::predicate::parse`] before
+/// sending them.
 const _PREDICATE_DSL_DOCS: () = ();
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -304,6 +304,35 @@ fn test_playlist_update_no_fields() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_playlist_create_and_update_reject_invalid_predicate() {
+    let mock_server = MockServer::start();
+    let post_mock = mock_server.mock(|when, then| {
+        when.method(POST).path("/v4/playlists");
+        then.status(201).json_body(json!([]));
+    });
+
+    let auth = setup_auth(&mock_server);
+    let result = PlaylistTools::create(
+        &auth,
+        "New Playlist",
+        Some("$TIME BETWEEN {32400000, 612000000}".to_string()),
+        None,
+        None,
+    );
+    assert!(result.unwrap_err().contains("at column 26"));
+    let result = PlaylistTools::update(
+        &auth,
+        "playlist-uuid",
+        None,
+        Some("$WEEKDAY IN {1, 2".to_string()),
+        None,
+        None,
+    );
+    assert!(result.unwrap_err().starts_with("Invalid predicate"));
+    post_mock.assert_calls(0);
+}
+
 #[test]
 fn test_playlist_delete() {
     let mock_server = MockServer::start();
@@ -4,6 +4,7 @@ use serde_json::json;
 
 use crate::authentication::Authentication;
 use crate::commands;
+use crate::commands::pagination::{self, Pagination};
 
 /// Asset tools for the MCP server.
 pub struct AssetTools;
@@ -11,8 +12,12 @@ pub struct AssetTools;
 impl AssetTools {
     /// List all assets (excluding edge-app-file type).
     pub fn list(auth: &Authentication) -> Result<String, String> {
-        let result = commands::get(auth, "v4/assets?type=neq.edge-app-file")
-            .map_err(|e| format!("Failed to list assets: {}", e))?;
+        let result = pagination::get_all(
+            auth,
+            "v4/assets?type=neq.edge-app-file",
+            Pagination::default(),
+        )
+        .map_err(|e| format!("Failed to list assets: {}", e))?;
 
         serde_json::to_string_pretty(&result)
             .map_err(|e| format!("Failed to serialize response: {}", e))
@@ -4,6 +4,7 @@ use serde_json::json;
 
 use crate::authentication::Authentication;
 use crate::commands;
+use crate::commands::pagination::{self, Pagination};
 
 /// Asset group tools for the MCP server.
 pub struct AssetGroupTools;
@@ -11,7 +12,7 @@ pub struct AssetGroupTools;
 impl AssetGroupTools {
     /// List all asset groups.
     pub fn list(auth: &Authentication) -> Result<String, String> {
-        let result = commands::get(auth, "v4/asset-groups")
+        let result = pagination::get_all(auth, "v4/asset-groups", Pagination::default())
             .map_err(|e| format!("Failed to list asset groups: {}", e))?;
 
         serde_json::to_string_pretty(&result)
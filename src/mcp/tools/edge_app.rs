@@ -2,6 +2,7 @@
 
 use crate::authentication::Authentication;
 use crate::commands;
+use crate::commands::pagination::{self, Pagination};
 
 /// Edge App tools for the MCP server.
 pub struct EdgeAppTools;
@@ -9,8 +10,12 @@ pub struct EdgeAppTools;
 impl EdgeAppTools {
     /// List all Edge Apps.
     pub fn list(auth: &Authentication) -> Result<String, String> {
-        let result = commands::get(auth, "v4/edge-apps?select=id,name&deleted=eq.false")
-            .map_err(|e| format!("Failed to list Edge Apps: {}", e))?;
+        let result = pagination::get_all(
+            auth,
+            "v4/edge-apps?select=id,name&deleted=eq.false",
+            Pagination::default(),
+        )
+        .map_err(|e| format!("Failed to list Edge Apps: {}", e))?;
 
         serde_json::to_string_pretty(&result)
             .map_err(|e| format!("Failed to serialize response: {}", e))
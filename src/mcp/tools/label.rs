@@ -4,6 +4,7 @@ use serde_json::json;
 
 use crate::authentication::Authentication;
 use crate::commands;
+use crate::commands::pagination::{self, Pagination};
 
 /// Label tools for the MCP server.
 pub struct LabelTools;
@@ -11,7 +12,7 @@ pub struct LabelTools;
 impl LabelTools {
     /// List all labels.
     pub fn list(auth: &Authentication) -> Result<String, String> {
-        let result = commands::get(auth, "v4/labels")
+        let result = pagination::get_all(auth, "v4/labels", Pagination::default())
             .map_err(|e| format!("Failed to list labels: {}", e))?;
 
         serde_json::to_string_pretty(&result)
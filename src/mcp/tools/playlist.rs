@@ -4,6 +4,8 @@ use serde_json::json;
 
 use crate::authentication::Authentication;
 use crate::commands;
+use crate::commands::pagination::{self, Pagination};
+use crate::commands::predicate;
 
 /// Playlist tools for the MCP server.
 pub struct PlaylistTools;
@@ -11,7 +13,7 @@ pub struct PlaylistTools;
 impl PlaylistTools {
     /// List all playlists.
     pub fn list(auth: &Authentication) -> Result<String, String> {
-        let result = commands::get(auth, "v4/playlists")
+        let result = pagination::get_all(auth, "v4/playlists", Pagination::default())
             .map_err(|e| format!("Failed to list playlists: {}", e))?;
 
         serde_json::to_string_pretty(&result)
@@ -26,9 +28,11 @@ impl PlaylistTools {
         priority: Option<bool>,
         is_enabled: Option<bool>,
     ) -> Result<String, String> {
+        let predicate = predicate.unwrap_or_else(|| "TRUE".to_string());
+        validate_predicate(&predicate)?;
         let payload = json!({
             "title": title,
-            "predicate": predicate.unwrap_or_else(|| "TRUE".to_string()),
+            "predicate": predicate,
             "priority": priority.unwrap_or(false),
             "is_enabled": is_enabled.unwrap_or(true),
             "transitions": true
@@ -57,6 +61,7 @@ impl PlaylistTools {
         }
 
         if let Some(p) = predicate {
+            validate_predicate(&p)?;
             payload.insert("predicate".to_string(), json!(p));
         }
 
@@ -89,3 +94,10 @@ impl PlaylistTools {
         Ok(json!({"status": "deleted", "id": uuid}).to_string())
     }
 }
+
+// Catches malformed predicates before the API stores a playlist that never shows.
+fn validate_predicate(predicate: &str) -> Result<(), String> {
+    predicate::parse(predicate)
+        .map(|_| ())
+        .map_err(|e| format!("Invalid predicate: {}", e))
+}
@@ -4,6 +4,7 @@ use serde_json::json;
 
 use crate::authentication::Authentication;
 use crate::commands;
+use crate::commands::pagination::{self, Pagination};
 
 /// Position spacing for playlist items. Uses large gaps (100,000) between items
 /// to allow inserting new items between existing ones without reordering.
@@ -20,7 +21,7 @@ impl PlaylistItemTools {
             "v4/playlist-items?playlist_id=eq.{}&order=position.asc",
             playlist_uuid
         );
-        let result = commands::get(auth, &endpoint)
+        let result = pagination::get_all(auth, &endpoint, Pagination::default())
             .map_err(|e| format!("Failed to list playlist items: {}", e))?;
 
         serde_json::to_string_pretty(&result)
@@ -2,6 +2,7 @@
 
 use crate::authentication::Authentication;
 use crate::commands;
+use crate::commands::pagination::{self, Pagination};
 
 /// Screen tools for the MCP server.
 pub struct ScreenTools;
@@ -9,7 +10,7 @@ pub struct ScreenTools;
 impl ScreenTools {
     /// List all screens.
     pub fn list(auth: &Authentication) -> Result<String, String> {
-        let result = commands::get(auth, "v4/screens")
+        let result = pagination::get_all(auth, "v4/screens", Pagination::default())
             .map_err(|e| format!("Failed to list screens: {}", e))?;
 
         serde_json::to_string_pretty(&result)
@@ -4,6 +4,7 @@ use serde_json::json;
 
 use crate::authentication::Authentication;
 use crate::commands;
+use crate::commands::pagination::{self, Pagination};
 
 /// Shared playlist tools for the MCP server.
 pub struct SharedPlaylistTools;
@@ -11,7 +12,7 @@ pub struct SharedPlaylistTools;
 impl SharedPlaylistTools {
     /// List all shared playlists.
     pub fn list(auth: &Authentication) -> Result<String, String> {
-        let result = commands::get(auth, "v4/playlists/shared")
+        let result = pagination::get_all(auth, "v4/playlists/shared", Pagination::default())
             .map_err(|e| format!("Failed to list shared playlists: {}", e))?;
 
         serde_json::to_string_pretty(&result)